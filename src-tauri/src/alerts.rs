@@ -0,0 +1,463 @@
+// src/alerts.rs
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State, Wry};
+use tokio::sync::OnceCell;
+use zbus::zvariant::Value;
+use zbus::{Connection, Proxy};
+
+use crate::model::SystemData;
+use crate::system::{MonitorTopic, SystemMonitor, TopicIntervals};
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+pub const ALERT_EVENT: &str = "system-alert";
+const RULES_FILE: &str = "alert-rules.json";
+/// Label of the monitor subscription the engine holds while any rule is enabled,
+/// so rules are checked with no window listening. That keeps the sampler running
+/// in the background: the sections the enabled rules need are refreshed every
+/// 5 s (30 s for disks), a few ms of CPU each. With no rule enabled there is no
+/// subscription and nothing is sampled while the windows are hidden.
+const MONITOR_LABEL: &str = "__alerts";
+const NOTIFICATIONS: (&str, &str) = ("org.freedesktop.Notifications", "/org/freedesktop/Notifications");
+
+// -----------------------------------------------------------------------------
+// Rules
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlertMetric {
+    /// Hottest sensor, or only the hwmon sensor with ID `sensor` or, given a chip
+    /// prefix such as "nvme/0000:01:00.0", the sensors under it.
+    Temperature { sensor: Option<String> },
+    DiskFreePercent { mount_point: String },
+    MemoryUsedPercent,
+    SwapUsedPercent,
+    CpuUsagePercent,
+    /// One-minute load average.
+    LoadAverage,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NamedThreshold {
+    /// The sensor's own `critical` (hwmon tempN_crit); sensors without one are ignored.
+    SensorCritical,
+    /// Number of physical cores, for load averages.
+    CoreCount,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum AlertThreshold {
+    Value(f64),
+    Named(NamedThreshold),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AlertRule {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub metric: AlertMetric,
+    pub comparison: Comparison,
+    pub threshold: AlertThreshold,
+    /// The condition has to hold this long before the alert fires.
+    pub duration_s: u64,
+    /// A firing alert resolves only once the value is this far back on the safe side.
+    pub hysteresis: f64,
+    /// Minimum time between two notifications of the same rule.
+    pub cooldown_s: u64,
+    pub notify: bool,
+}
+
+/// Examples to start from. They ship disabled, so alerting (and the background
+/// sampling it needs) only starts once the user turns a rule on.
+fn default_rules() -> Vec<AlertRule> {
+    let rule = |id: &str, name: &str, metric, comparison, threshold, duration_s, hysteresis, cooldown_s| AlertRule {
+        id: id.into(),
+        name: name.into(),
+        enabled: false,
+        metric,
+        comparison,
+        threshold,
+        duration_s,
+        hysteresis,
+        cooldown_s,
+        notify: true,
+    };
+    vec![
+        rule(
+            "cpu-temperature-critical",
+            "Temperature above critical",
+            AlertMetric::Temperature { sensor: None },
+            Comparison::Above,
+            AlertThreshold::Named(NamedThreshold::SensorCritical),
+            30,
+            5.0,
+            600,
+        ),
+        rule(
+            "root-disk-low",
+            "Root disk almost full",
+            AlertMetric::DiskFreePercent { mount_point: "/".into() },
+            Comparison::Below,
+            AlertThreshold::Value(5.0),
+            0,
+            1.0,
+            3600,
+        ),
+        rule(
+            "swap-high",
+            "Swap usage high",
+            AlertMetric::SwapUsedPercent,
+            Comparison::Above,
+            AlertThreshold::Value(80.0),
+            60,
+            5.0,
+            1800,
+        ),
+        rule(
+            "load-high",
+            "Load above core count",
+            AlertMetric::LoadAverage,
+            Comparison::Above,
+            AlertThreshold::Named(NamedThreshold::CoreCount),
+            120,
+            0.5,
+            1800,
+        ),
+    ]
+}
+
+fn validate(rules: &[AlertRule]) -> Result<(), String> {
+    for (i, rule) in rules.iter().enumerate() {
+        if rule.id.trim().is_empty() {
+            return Err(format!("Rule {} has no id.", i + 1));
+        }
+        if rules[..i].iter().any(|r| r.id == rule.id) {
+            return Err(format!("Duplicate rule id '{}'.", rule.id));
+        }
+        if rule.hysteresis < 0.0 {
+            return Err(format!("Rule '{}': hysteresis cannot be negative.", rule.id));
+        }
+        let named_ok = match (&rule.threshold, &rule.metric) {
+            (AlertThreshold::Named(NamedThreshold::SensorCritical), AlertMetric::Temperature { .. }) => true,
+            (AlertThreshold::Named(NamedThreshold::CoreCount), AlertMetric::LoadAverage) => true,
+            (AlertThreshold::Named(_), _) => false,
+            (AlertThreshold::Value(v), _) => v.is_finite(),
+        };
+        if !named_ok {
+            return Err(format!("Rule '{}': threshold does not fit the metric.", rule.id));
+        }
+    }
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Evaluation
+// -----------------------------------------------------------------------------
+
+/// Current value and threshold for a rule, or None when the metric is unavailable.
+fn measure(rule: &AlertRule, data: &SystemData) -> Option<(f64, f64)> {
+    let fixed = |v: f64| match rule.threshold {
+        AlertThreshold::Value(t) => Some((v, t)),
+        AlertThreshold::Named(NamedThreshold::CoreCount) => {
+            let cores = data.cpu.physical_cores.unwrap_or(data.cpu.individual_cpus.len());
+            Some((v, cores as f64))
+        }
+        AlertThreshold::Named(NamedThreshold::SensorCritical) => None,
+    };
+    let percent = |used: f64, total: f64| (total > 0.0).then(|| used / total * 100.0);
+
+    match &rule.metric {
+        AlertMetric::Temperature { sensor } => {
+            let sensors = data.sensors.iter().filter(|s| s.kind == "temperature").filter(|s| {
+                sensor.as_ref().is_none_or(|id| {
+                    s.id.strip_prefix(id.as_str()).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                })
+            });
+            // The sensor closest to (or furthest past) its own threshold decides.
+            sensors
+                .filter_map(|s| {
+                    let threshold = match rule.threshold {
+                        AlertThreshold::Named(NamedThreshold::SensorCritical) => s.critical?,
+                        AlertThreshold::Value(t) => t,
+                        AlertThreshold::Named(NamedThreshold::CoreCount) => return None,
+                    };
+                    Some((s.value, threshold))
+                })
+                .max_by(|a, b| (a.0 - a.1).total_cmp(&(b.0 - b.1)))
+        }
+        AlertMetric::DiskFreePercent { mount_point } => data
+            .disks
+            .iter()
+            .find(|d| &d.mount_point == mount_point)
+            .and_then(|d| percent(d.available_gb, d.total_gb))
+            .and_then(fixed),
+        AlertMetric::MemoryUsedPercent => {
+            percent(data.memory.used_kb as f64, data.memory.total_kb as f64).and_then(fixed)
+        }
+        AlertMetric::SwapUsedPercent => {
+            percent(data.memory.used_swap_kb as f64, data.memory.total_swap_kb as f64).and_then(fixed)
+        }
+        AlertMetric::CpuUsagePercent => fixed(data.cpu.global_usage_percent as f64),
+        AlertMetric::LoadAverage => fixed(data.load_average.one_min),
+    }
+}
+
+#[derive(Debug, Default)]
+struct RuleState {
+    breach_since: Option<Instant>,
+    firing: bool,
+    last_notified: Option<Instant>,
+    last_value: f64,
+    last_threshold: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AlertEvent {
+    pub rule_id: String,
+    pub name: String,
+    /// "firing" or "resolved"
+    pub state: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub message: String,
+    pub timestamp: String,
+}
+
+/// Rule set plus per-rule state; fed by the system monitor after every sample.
+#[derive(Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    states: HashMap<String, RuleState>,
+}
+
+impl AlertEngine {
+    pub fn evaluate(&mut self, data: &SystemData, app_handle: &AppHandle<Wry>) {
+        let now = Instant::now();
+        for rule in self.rules.iter().filter(|r| r.enabled) {
+            let state = self.states.entry(rule.id.clone()).or_default();
+            let Some((value, threshold)) = measure(rule, data) else { continue };
+            state.last_value = value;
+            state.last_threshold = threshold;
+
+            let (breached, cleared) = match rule.comparison {
+                Comparison::Above => (value > threshold, value <= threshold - rule.hysteresis),
+                Comparison::Below => (value < threshold, value >= threshold + rule.hysteresis),
+            };
+
+            if state.firing {
+                if cleared {
+                    state.firing = false;
+                    state.breach_since = None;
+                    emit(app_handle, rule, "resolved", value, threshold);
+                }
+                continue;
+            }
+
+            if !breached {
+                state.breach_since = None;
+                continue;
+            }
+            let since = *state.breach_since.get_or_insert(now);
+            if now.duration_since(since) < Duration::from_secs(rule.duration_s) {
+                continue;
+            }
+
+            state.firing = true;
+            let event = emit(app_handle, rule, "firing", value, threshold);
+            let cooled_down = state
+                .last_notified
+                .is_none_or(|t| now.duration_since(t) >= Duration::from_secs(rule.cooldown_s));
+            if rule.notify && cooled_down {
+                state.last_notified = Some(now);
+                notify_desktop(&event);
+            }
+        }
+    }
+
+    fn active(&self) -> Vec<AlertEvent> {
+        self.rules
+            .iter()
+            .filter_map(|rule| {
+                let state = self.states.get(&rule.id).filter(|s| s.firing)?;
+                Some(event(rule, "firing", state.last_value, state.last_threshold))
+            })
+            .collect()
+    }
+
+    /// Keeps the state of rules whose definition did not change.
+    fn set_rules(&mut self, rules: Vec<AlertRule>) {
+        let old = std::mem::take(&mut self.rules);
+        self.states.retain(|id, _| {
+            let before = old.iter().find(|r| &r.id == id);
+            let after = rules.iter().find(|r| &r.id == id);
+            matches!((before, after), (Some(b), Some(a)) if serde_json::to_value(b).ok() == serde_json::to_value(a).ok())
+        });
+        self.rules = rules;
+    }
+
+    /// What the enabled rules need sampled, at a pace fit for alerting.
+    fn topics(&self) -> Option<TopicIntervals> {
+        let mut topics = TopicIntervals::new();
+        for rule in self.rules.iter().filter(|r| r.enabled) {
+            let (topic, secs) = match rule.metric {
+                AlertMetric::Temperature { .. } => (MonitorTopic::Sensors, 5),
+                AlertMetric::DiskFreePercent { .. } => (MonitorTopic::Disks, 30),
+                AlertMetric::MemoryUsedPercent | AlertMetric::SwapUsedPercent => (MonitorTopic::Memory, 5),
+                AlertMetric::CpuUsagePercent | AlertMetric::LoadAverage => (MonitorTopic::Cpu, 5),
+            };
+            topics.insert(topic, Duration::from_secs(secs));
+        }
+        (!topics.is_empty()).then_some(topics)
+    }
+}
+
+fn event(rule: &AlertRule, state: &str, value: f64, threshold: f64) -> AlertEvent {
+    let message = match state {
+        "firing" => format!("{}: {:.1} (threshold {:.1})", rule.name, value, threshold),
+        _ => format!("{} resolved: {:.1}", rule.name, value),
+    };
+    AlertEvent {
+        rule_id: rule.id.clone(),
+        name: rule.name.clone(),
+        state: state.to_string(),
+        value,
+        threshold,
+        message,
+        timestamp: Utc::now().to_rfc3339(),
+    }
+}
+
+fn emit(app_handle: &AppHandle<Wry>, rule: &AlertRule, state: &str, value: f64, threshold: f64) -> AlertEvent {
+    let event = event(rule, state, value, threshold);
+    if let Err(e) = app_handle.emit(ALERT_EVENT, &event) {
+        eprintln!("Failed to emit alert event: {}", e);
+    }
+    event
+}
+
+/// Goes through the notification daemon, so it shows with every window hidden.
+/// Rules are evaluated on the monitor's blocking thread; the D-Bus call is not.
+fn notify_desktop(event: &AlertEvent) {
+    let (summary, body) = (event.name.clone(), event.message.clone());
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = send_notification(&summary, &body).await {
+            eprintln!("Failed to send desktop notification: {}", e);
+        }
+    });
+}
+
+/// org.freedesktop.Notifications.Notify with critical urgency, over one session
+/// bus connection kept for the lifetime of the app.
+async fn send_notification(summary: &str, body: &str) -> zbus::Result<()> {
+    static SESSION: OnceCell<Connection> = OnceCell::const_new();
+    let conn = SESSION.get_or_try_init(Connection::session).await?;
+    let (service, path) = NOTIFICATIONS;
+    let proxy = Proxy::new(conn, service, path, service).await?;
+    let hints: HashMap<&str, Value> = HashMap::from([("urgency", Value::from(2u8))]);
+    let actions: Vec<&str> = Vec::new();
+    let _id: u32 = proxy
+        .call("Notify", &("LinuxHub", 0u32, "dialog-warning", summary, body, actions, hints, -1i32))
+        .await?;
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Persistence
+// -----------------------------------------------------------------------------
+fn rules_path(app_handle: &AppHandle<Wry>) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(RULES_FILE))
+        .map_err(|e| format!("No app data directory: {}", e))
+}
+
+fn load_rules(app_handle: &AppHandle<Wry>) -> Vec<AlertRule> {
+    rules_path(app_handle)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| match serde_json::from_str(&raw) {
+            Ok(rules) => Some(rules),
+            Err(e) => {
+                eprintln!("Ignoring invalid {}: {}", RULES_FILE, e);
+                None
+            }
+        })
+        .unwrap_or_else(default_rules)
+}
+
+fn save_rules(app_handle: &AppHandle<Wry>, rules: &[AlertRule]) -> Result<(), String> {
+    let path = rules_path(app_handle)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("create {}: {}", dir.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(rules).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("write {}: {}", path.display(), e))
+}
+
+/// Installs `rules` and keeps the engine's own monitor subscription in line with them.
+fn install_rules(app_handle: &AppHandle<Wry>, monitor: &SystemMonitor, rules: Vec<AlertRule>) {
+    let alerts = monitor.alerts();
+    let topics = {
+        let mut engine = alerts.lock().unwrap();
+        engine.set_rules(rules);
+        engine.topics()
+    };
+    monitor.set_background_subscription(app_handle.clone(), MONITOR_LABEL, topics);
+}
+
+/// Called once at startup.
+pub fn start(app_handle: &AppHandle<Wry>) {
+    let rules = load_rules(app_handle);
+    install_rules(app_handle, &app_handle.state::<SystemMonitor>(), rules);
+}
+
+// -----------------------------------------------------------------------------
+// Tauri commands
+// -----------------------------------------------------------------------------
+#[tauri::command]
+pub fn get_alert_rules(monitor: State<'_, SystemMonitor>) -> Result<Vec<AlertRule>, String> {
+    Ok(monitor.alerts().lock().unwrap().rules.clone())
+}
+
+/// Replaces the whole rule set; an empty list disables alerting.
+#[tauri::command]
+pub fn save_alert_rules(
+    app_handle: AppHandle<Wry>,
+    monitor: State<'_, SystemMonitor>,
+    rules: Vec<AlertRule>,
+) -> Result<Vec<AlertRule>, String> {
+    validate(&rules)?;
+    save_rules(&app_handle, &rules)?;
+    install_rules(&app_handle, &monitor, rules.clone());
+    Ok(rules)
+}
+
+#[tauri::command]
+pub fn reset_alert_rules(app_handle: AppHandle<Wry>, monitor: State<'_, SystemMonitor>) -> Result<Vec<AlertRule>, String> {
+    let rules = default_rules();
+    save_rules(&app_handle, &rules)?;
+    install_rules(&app_handle, &monitor, rules.clone());
+    Ok(rules)
+}
+
+#[tauri::command]
+pub fn get_active_alerts(monitor: State<'_, SystemMonitor>) -> Result<Vec<AlertEvent>, String> {
+    Ok(monitor.alerts().lock().unwrap().active())
+}
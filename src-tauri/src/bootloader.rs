@@ -0,0 +1,588 @@
+// src/bootloader.rs
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+
+use crate::privileged::{read_file, run_as_root, write_file, FileChange};
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+pub const GRUB_DEFAULTS_PATH: &str = "/etc/default/grub";
+pub const GRUB_CFG_PATH: &str = "/boot/grub/grub.cfg";
+/// Tried in order when `bootctl --print-esp-path` is not available.
+const ESP_CANDIDATES: [&str; 3] = ["/efi", "/boot", "/boot/efi"];
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BootloaderKind {
+    SystemdBoot,
+    Grub,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BootEntry {
+    /// Value to pass as the default entry: the systemd-boot entry id, or the
+    /// GRUB menu path ("submenu>entry", ids when GRUB provides them).
+    pub id: String,
+    pub title: String,
+    pub kernel: Option<String>,
+    pub initrd: Vec<String>,
+    pub cmdline: Option<String>,
+    pub is_default: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BootloaderInfo {
+    pub kind: BootloaderKind,
+    pub config_path: String,
+    pub default_entry: Option<String>,
+    pub timeout_s: Option<u32>,
+    pub entries: Vec<BootEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BootloaderChangePreview {
+    pub kind: BootloaderKind,
+    pub files: Vec<FileChange>,
+    /// Commands run after the files are written, e.g. grub-mkconfig.
+    pub commands: Vec<String>,
+    pub applied: bool,
+    /// `commands` as argument vectors, for `apply_bootloader_changes`.
+    #[serde(skip)]
+    argv: Vec<Vec<String>>,
+}
+
+/// One level of a GRUB menu path: the menuentry or submenu id, its title and its
+/// position among its siblings, any of which GRUB_DEFAULT may use.
+#[derive(Debug, Clone, PartialEq)]
+struct MenuLevel {
+    id: String,
+    title: String,
+    index: usize,
+}
+
+// -----------------------------------------------------------------------------
+// Detection
+// -----------------------------------------------------------------------------
+pub fn detect_bootloader() -> Option<BootloaderKind> {
+    if loader_conf_path().is_some() {
+        return Some(BootloaderKind::SystemdBoot);
+    }
+    if Path::new(GRUB_DEFAULTS_PATH).exists() || Path::new(GRUB_CFG_PATH).exists() {
+        return Some(BootloaderKind::Grub);
+    }
+    None
+}
+
+pub fn esp_path() -> Option<String> {
+    let from_bootctl = Command::new("bootctl")
+        .arg("--print-esp-path")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|p| !p.is_empty());
+
+    from_bootctl.or_else(|| {
+        ESP_CANDIDATES
+            .iter()
+            .find(|esp| Path::new(esp).join("loader").is_dir())
+            .map(|esp| esp.to_string())
+    })
+}
+
+fn loader_conf_path() -> Option<String> {
+    let esp = esp_path()?;
+    let path = format!("{}/loader/loader.conf", esp);
+    // The ESP is often mounted root-only; the loader directory is enough to tell.
+    Path::new(&esp).join("loader").is_dir().then_some(path)
+}
+
+// -----------------------------------------------------------------------------
+// systemd-boot
+// -----------------------------------------------------------------------------
+fn systemd_boot_info() -> Result<BootloaderInfo, String> {
+    let config_path = loader_conf_path().ok_or("systemd-boot loader.conf not found")?;
+    let loader_conf = read_file(&config_path).unwrap_or_default();
+    let timeout_s = loader_option(&loader_conf, "timeout").and_then(|t| t.parse().ok());
+
+    // `bootctl list` resolves type #1 and #2 (UKI) entries and knows the effective default.
+    // On a root-only ESP it fails or finds nothing as the user, so ask again as root.
+    let args = ["list", "--json=short", "--no-pager"];
+    let output = Command::new("bootctl")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute bootctl: {}", e))?;
+    let listed: Vec<serde_json::Value> = match serde_json::from_slice::<Vec<serde_json::Value>>(&output.stdout) {
+        Ok(listed) if output.status.success() && !listed.is_empty() => listed,
+        _ => serde_json::from_str(&run_as_root("bootctl", &args)?)
+            .map_err(|e| format!("Failed to parse bootctl output: {}", e))?,
+    };
+
+    let entries: Vec<BootEntry> = listed
+        .iter()
+        .map(|entry| {
+            let text = |key: &str| entry.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+            BootEntry {
+                id: text("id").unwrap_or_default(),
+                title: text("showTitle").or_else(|| text("title")).unwrap_or_default(),
+                kernel: text("linux"),
+                initrd: entry
+                    .get("initrd")
+                    .and_then(|v| v.as_array())
+                    .map(|a| a.iter().filter_map(|i| i.as_str().map(|s| s.to_string())).collect())
+                    .unwrap_or_default(),
+                cmdline: text("options"),
+                is_default: entry.get("isDefault").and_then(|v| v.as_bool()).unwrap_or(false),
+            }
+        })
+        .collect();
+
+    // The LoaderEntryDefault EFI variable (bootctl set-default) overrides loader.conf;
+    // bootctl reports the default that actually applies.
+    let default_entry = entries
+        .iter()
+        .find(|e| e.is_default)
+        .map(|e| e.id.clone())
+        .or_else(|| loader_option(&loader_conf, "default"));
+
+    Ok(BootloaderInfo {
+        kind: BootloaderKind::SystemdBoot,
+        config_path,
+        default_entry,
+        timeout_s,
+        entries,
+    })
+}
+
+/// loader.conf uses "key value" lines.
+pub fn loader_option(content: &str, key: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let mut parts = line.trim().splitn(2, char::is_whitespace);
+        (parts.next()? == key).then(|| parts.next().unwrap_or("").trim().to_string())
+    })
+}
+
+pub fn set_loader_option(content: &str, key: &str, value: &str) -> String {
+    let new_line = format!("{} {}", key, value);
+    let mut found = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let is_key = line.split_whitespace().next() == Some(key);
+            if is_key && !found {
+                found = true;
+                new_line.clone()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !found {
+        lines.push(new_line);
+    }
+    lines.join("\n") + "\n"
+}
+
+// -----------------------------------------------------------------------------
+// GRUB
+// -----------------------------------------------------------------------------
+fn grub_info() -> Result<BootloaderInfo, String> {
+    let defaults = read_file(GRUB_DEFAULTS_PATH).unwrap_or_default();
+    let timeout_s = shell_var(&defaults, "GRUB_TIMEOUT").and_then(|t| t.parse().ok());
+
+    let mut default_entry = shell_var(&defaults, "GRUB_DEFAULT");
+    if default_entry.as_deref() == Some("saved") {
+        default_entry = grub_saved_entry().or(default_entry);
+    }
+
+    let grub_cfg = read_file(GRUB_CFG_PATH)?;
+    let default_key = default_entry.clone().unwrap_or_else(|| "0".to_string());
+    let entries = parse_grub_cfg(&grub_cfg)
+        .into_iter()
+        .map(|(mut entry, path)| {
+            entry.is_default = is_menu_path(&path, &default_key);
+            entry
+        })
+        .collect();
+
+    Ok(BootloaderInfo {
+        kind: BootloaderKind::Grub,
+        config_path: GRUB_DEFAULTS_PATH.to_string(),
+        default_entry,
+        timeout_s,
+        entries,
+    })
+}
+
+fn grub_saved_entry() -> Option<String> {
+    let output = Command::new("grub-editenv").arg("list").output().ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|l| l.strip_prefix("saved_entry=").map(|s| s.to_string()))
+}
+
+/// Whether a GRUB_DEFAULT / saved_entry value ("2", "1>0", "Advanced options>Arch
+/// Linux, with linux-lts", "gnulinux-advanced-…>gnulinux-lts-…") selects this entry.
+/// Every level may be given as an index, an id or a title.
+fn is_menu_path(path: &[MenuLevel], key: &str) -> bool {
+    let parts: Vec<&str> = key.split('>').collect();
+    parts.len() == path.len()
+        && parts
+            .iter()
+            .zip(path)
+            .all(|(part, level)| *part == level.id || *part == level.title || part.parse() == Ok(level.index))
+}
+
+/// Walks `menuentry` / `submenu` blocks of a generated grub.cfg.
+fn parse_grub_cfg(content: &str) -> Vec<(BootEntry, Vec<MenuLevel>)> {
+    let mut entries = Vec::new();
+    let mut submenus: Vec<MenuLevel> = Vec::new();
+    // Items seen so far on each open menu level, the top level first.
+    let mut counts: Vec<usize> = vec![0];
+    let mut current: Option<(BootEntry, Vec<MenuLevel>)> = None;
+
+    for line in content.lines().map(str::trim) {
+        let is_submenu = line.starts_with("submenu ");
+        if is_submenu || line.starts_with("menuentry ") {
+            let count = counts.last_mut().expect("top level is never closed");
+            let level = MenuLevel {
+                id: grub_block_id(line),
+                title: first_quoted(line).unwrap_or_default(),
+                index: *count,
+            };
+            *count += 1;
+            if is_submenu {
+                submenus.push(level);
+                counts.push(0);
+                continue;
+            }
+            let mut path = submenus.clone();
+            path.push(level);
+            let entry = BootEntry {
+                id: path.iter().map(|l| l.id.as_str()).collect::<Vec<_>>().join(">"),
+                title: first_quoted(line).unwrap_or_default(),
+                kernel: None,
+                initrd: Vec::new(),
+                cmdline: None,
+                is_default: false,
+            };
+            current = Some((entry, path));
+        } else if let Some((entry, _)) = current.as_mut() {
+            // grub-mkconfig separates the command from its arguments with a tab.
+            let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            if command == "linux" || command == "linuxefi" {
+                let mut parts = rest.trim().splitn(2, char::is_whitespace);
+                entry.kernel = parts.next().map(|s| s.to_string());
+                entry.cmdline = parts.next().map(|s| s.trim().to_string());
+            } else if command == "initrd" || command == "initrdefi" {
+                entry.initrd = rest.split_whitespace().map(|s| s.to_string()).collect();
+            } else if line == "}" {
+                entries.push(current.take().unwrap());
+            }
+        } else if line == "}" && submenus.pop().is_some() {
+            counts.pop();
+        }
+    }
+    entries
+}
+
+// Prefer `$menuentry_id_option 'gnulinux-...'`, fall back to the title.
+fn grub_block_id(line: &str) -> String {
+    line.split_once("$menuentry_id_option")
+        .and_then(|(_, rest)| first_quoted(rest))
+        .or_else(|| first_quoted(line))
+        .unwrap_or_default()
+}
+
+fn first_quoted(text: &str) -> Option<String> {
+    let start = text.find(['\'', '"'])?;
+    let quote = text[start..].chars().next()?;
+    let rest = &text[start + 1..];
+    let end = rest.find(quote)?;
+    Some(rest[..end].to_string())
+}
+
+/// The value of an active `KEY=...` line, with `export` allowed in front.
+fn assignment<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let line = line.trim();
+    let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+    let (k, v) = line.split_once('=')?;
+    (k == key).then_some(v)
+}
+
+/// Reads `KEY=value` / `KEY="value"` / `KEY='value'` from a shell-style config file.
+/// As in the shell, the last assignment wins and a `#` after an unquoted value
+/// starts a comment.
+pub fn shell_var(content: &str, key: &str) -> Option<String> {
+    let raw = content.lines().rev().find_map(|line| assignment(line, key))?;
+    let mut chars = raw.chars();
+    let value = match chars.next() {
+        Some('\'') => chars.take_while(|&c| c != '\'').collect(),
+        Some('"') => {
+            let mut value = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => value.extend(chars.next()),
+                    c => value.push(c),
+                }
+            }
+            value
+        }
+        _ => raw.split(|c: char| c.is_whitespace() || c == '#').next().unwrap_or("").to_string(),
+    };
+    Some(value)
+}
+
+/// Sets `KEY="value"`, replacing the last active assignment (the one the shell
+/// uses) or appending one.
+pub fn set_shell_var(content: &str, key: &str, value: &str) -> String {
+    let new_line = format!("{}=\"{}\"", key, value.replace('"', "\\\""));
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    match lines.iter().rposition(|line| assignment(line, key).is_some()) {
+        Some(i) => lines[i] = new_line,
+        None => lines.push(new_line),
+    }
+    lines.join("\n") + "\n"
+}
+
+pub fn grub_mkconfig_command() -> Vec<&'static str> {
+    vec!["grub-mkconfig", "-o", GRUB_CFG_PATH]
+}
+
+// -----------------------------------------------------------------------------
+// Changes: computed once for the preview and again when applying
+// -----------------------------------------------------------------------------
+fn plan_changes(default_entry: Option<&str>, timeout_s: Option<u32>) -> Result<BootloaderChangePreview, String> {
+    let kind = detect_bootloader().ok_or("No supported bootloader (systemd-boot or GRUB) detected.")?;
+
+    let (path, original) = match kind {
+        BootloaderKind::SystemdBoot => {
+            let path = loader_conf_path().ok_or("systemd-boot loader.conf not found")?;
+            let original = read_file(&path).unwrap_or_default();
+            (path, original)
+        }
+        BootloaderKind::Grub => (GRUB_DEFAULTS_PATH.to_string(), read_file(GRUB_DEFAULTS_PATH)?),
+    };
+
+    if let Some(entry) = default_entry {
+        check_entry_id(entry)?;
+    }
+
+    let mut updated = original.clone();
+    let mut argv: Vec<Vec<String>> = Vec::new();
+    match kind {
+        BootloaderKind::SystemdBoot => {
+            // A default set with `bootctl set-default` lives in an EFI variable that
+            // overrides loader.conf, so the default is always set that way.
+            if let Some(entry) = default_entry {
+                argv.push(vec!["bootctl".into(), "set-default".into(), entry.into()]);
+            }
+            if let Some(t) = timeout_s {
+                updated = set_loader_option(&updated, "timeout", &t.to_string());
+            }
+        }
+        BootloaderKind::Grub => {
+            // With GRUB_DEFAULT=saved the default lives in grubenv; keep that mode.
+            let saved = shell_var(&original, "GRUB_DEFAULT").as_deref() == Some("saved");
+            match default_entry {
+                Some(entry) if saved => argv.push(vec!["grub-set-default".into(), entry.into()]),
+                Some(entry) => updated = set_shell_var(&updated, "GRUB_DEFAULT", entry),
+                None => {}
+            }
+            if let Some(t) = timeout_s {
+                updated = set_shell_var(&updated, "GRUB_TIMEOUT", &t.to_string());
+            }
+        }
+    }
+
+    let change = FileChange::new(&path, original, updated);
+    if kind == BootloaderKind::Grub && !change.is_empty() {
+        argv.push(grub_mkconfig_command().into_iter().map(String::from).collect());
+    }
+    let files = if change.is_empty() { Vec::new() } else { vec![change] };
+    let commands = argv.iter().map(|a| a.join(" ")).collect();
+
+    Ok(BootloaderChangePreview { kind, files, commands, applied: false, argv })
+}
+
+/// Entry ids end up in a file grub-mkconfig sources and on root command lines.
+fn check_entry_id(entry: &str) -> Result<(), String> {
+    if entry.is_empty() || entry.starts_with('-') || entry.contains(['"', '$', '`', '\\', '\n']) {
+        return Err(format!("Invalid boot entry '{}'.", entry));
+    }
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Tauri commands
+// -----------------------------------------------------------------------------
+#[tauri::command]
+pub async fn get_bootloader_info() -> Result<BootloaderInfo, String> {
+    // read_file falls back to pkexec for root-only files.
+    tokio::task::spawn_blocking(|| match detect_bootloader() {
+        Some(BootloaderKind::SystemdBoot) => systemd_boot_info(),
+        Some(BootloaderKind::Grub) => grub_info(),
+        None => Err("No supported bootloader (systemd-boot or GRUB) detected.".into()),
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Shows the file edits `apply_bootloader_changes` would make, without writing anything.
+#[tauri::command]
+pub async fn preview_bootloader_changes(
+    default_entry: Option<String>,
+    timeout_s: Option<u32>,
+) -> Result<BootloaderChangePreview, String> {
+    tokio::task::spawn_blocking(move || plan_changes(default_entry.as_deref(), timeout_s))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn apply_bootloader_changes(
+    default_entry: Option<String>,
+    timeout_s: Option<u32>,
+) -> Result<BootloaderChangePreview, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut plan = plan_changes(default_entry.as_deref(), timeout_s)?;
+
+        for file in &plan.files {
+            write_file(&file.path, &file.updated)?;
+        }
+        for command in &plan.argv {
+            let args: Vec<&str> = command[1..].iter().map(String::as_str).collect();
+            run_as_root(&command[0], &args)?;
+        }
+
+        plan.applied = true;
+        Ok(plan)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRUB_CFG: &str = r#"
+menuentry 'Arch Linux' --class arch $menuentry_id_option 'gnulinux-simple-1234' {
+	linux	/vmlinuz-linux root=UUID=1234 rw quiet
+	initrd	/intel-ucode.img /initramfs-linux.img
+}
+submenu 'Advanced options for Arch Linux' $menuentry_id_option 'gnulinux-advanced-1234' {
+	menuentry 'Arch Linux, with Linux linux' $menuentry_id_option 'gnulinux-linux-advanced-1234' {
+		linux	/vmlinuz-linux root=UUID=1234 rw quiet
+	}
+	menuentry 'Arch Linux, with Linux linux-lts' $menuentry_id_option 'gnulinux-linux-lts-advanced-1234' {
+		linux	/vmlinuz-linux-lts root=UUID=1234 rw quiet
+	}
+}
+menuentry 'UEFI Firmware Settings' $menuentry_id_option 'uefi-firmware' {
+	fwsetup
+}
+"#;
+
+    #[test]
+    fn shell_var_reads_quoting_comments_and_missing_keys() {
+        let cases = [
+            ("GRUB_DEFAULT=0\n", Some("0")),
+            ("GRUB_DEFAULT=\"saved\"\n", Some("saved")),
+            ("GRUB_DEFAULT='1>2'\n", Some("1>2")),
+            ("GRUB_DEFAULT=\"a \\\"b\\\"\"\n", Some("a \"b\"")),
+            ("GRUB_DEFAULT=2 # second entry\n", Some("2")),
+            ("GRUB_DEFAULT=\"a # b\" # comment\n", Some("a # b")),
+            ("#GRUB_DEFAULT=3\n", None),
+            ("GRUB_DEFAULT=0\nGRUB_DEFAULT=saved\n", Some("saved")),
+            ("export GRUB_DEFAULT=1\n", Some("1")),
+            ("GRUB_DEFAULT_X=1\n", None),
+            ("GRUB_TIMEOUT=5\n", None),
+            ("", None),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(shell_var(content, "GRUB_DEFAULT").as_deref(), expected, "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn set_shell_var_replaces_the_active_assignment() {
+        let cases = [
+            ("GRUB_DEFAULT=0\nGRUB_TIMEOUT=5\n", "GRUB_DEFAULT=\"2\"\nGRUB_TIMEOUT=5\n"),
+            ("#GRUB_DEFAULT=0\n", "#GRUB_DEFAULT=0\nGRUB_DEFAULT=\"2\"\n"),
+            ("GRUB_DEFAULT=0\nGRUB_DEFAULT=1\n", "GRUB_DEFAULT=0\nGRUB_DEFAULT=\"2\"\n"),
+            ("", "GRUB_DEFAULT=\"2\"\n"),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(set_shell_var(content, "GRUB_DEFAULT", "2"), expected, "{:?}", content);
+        }
+        let quoted = set_shell_var("", "GRUB_DEFAULT", "a \"b\"");
+        assert_eq!(shell_var(&quoted, "GRUB_DEFAULT").as_deref(), Some("a \"b\""));
+    }
+
+    #[test]
+    fn loader_options_are_read_and_replaced() {
+        let conf = "#timeout 3\ntimeout 5\ndefault arch.conf\n";
+        assert_eq!(loader_option(conf, "timeout").as_deref(), Some("5"));
+        assert_eq!(loader_option(conf, "editor"), None);
+        assert_eq!(set_loader_option(conf, "timeout", "10"), "#timeout 3\ntimeout 10\ndefault arch.conf\n");
+        assert_eq!(set_loader_option("", "timeout", "10"), "timeout 10\n");
+    }
+
+    #[test]
+    fn grub_cfg_entries_carry_their_submenu_path() {
+        let entries = parse_grub_cfg(GRUB_CFG);
+        let ids: Vec<&str> = entries.iter().map(|(e, _)| e.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "gnulinux-simple-1234",
+                "gnulinux-advanced-1234>gnulinux-linux-advanced-1234",
+                "gnulinux-advanced-1234>gnulinux-linux-lts-advanced-1234",
+                "uefi-firmware",
+            ]
+        );
+        let (first, _) = &entries[0];
+        assert_eq!(first.kernel.as_deref(), Some("/vmlinuz-linux"));
+        assert_eq!(first.cmdline.as_deref(), Some("root=UUID=1234 rw quiet"));
+        assert_eq!(first.initrd, ["/intel-ucode.img", "/initramfs-linux.img"]);
+    }
+
+    #[test]
+    fn grub_default_selects_entries_by_index_id_or_title() {
+        let entries = parse_grub_cfg(GRUB_CFG);
+        let default_of = |key: &str| -> Vec<usize> {
+            (0..entries.len()).filter(|&i| is_menu_path(&entries[i].1, key)).collect()
+        };
+        let cases: [(&str, &[usize]); 8] = [
+            ("0", &[0]),
+            // The submenu is item 1, so the firmware entry is item 2.
+            ("2", &[3]),
+            ("1>1", &[2]),
+            ("gnulinux-advanced-1234>gnulinux-linux-lts-advanced-1234", &[2]),
+            ("Advanced options for Arch Linux>Arch Linux, with Linux linux", &[1]),
+            ("gnulinux-advanced-1234>0", &[1]),
+            ("1", &[]),
+            ("missing", &[]),
+        ];
+        for (key, expected) in cases {
+            assert_eq!(default_of(key), expected, "{}", key);
+        }
+    }
+
+    #[test]
+    fn entry_ids_that_reach_a_shell_are_rejected() {
+        for id in ["gnulinux-simple-1234", "1>2", "arch.conf", "Arch Linux, with Linux linux"] {
+            assert!(check_entry_id(id).is_ok(), "{}", id);
+        }
+        for id in ["", "--help", "a\"b", "$(reboot)", "`id`", "a\\b", "a\nb"] {
+            assert!(check_entry_id(id).is_err(), "{:?}", id);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+// src/hwmon.rs
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::model::HwmonSensor;
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+const HWMON: &str = "/sys/class/hwmon";
+/// RAPL (Intel, and AMD since Zen) exposes energy counters only; watts come from deltas.
+/// energy_uj is root-only on kernels patched for PLATYPUS, in which case RAPL is skipped.
+const POWERCAP: &str = "/sys/class/powercap";
+
+/// (file prefix, kind, unit, divisor from the sysfs unit)
+type InputKind = (&'static str, &'static str, &'static str, f64);
+const INPUT_KINDS: &[InputKind] = &[
+    ("temp", "temperature", "°C", 1_000.0),
+    ("fan", "fan", "RPM", 1.0),
+    ("in", "voltage", "V", 1_000.0),
+    ("curr", "current", "A", 1_000.0),
+    ("power", "power", "W", 1_000_000.0),
+];
+
+// -----------------------------------------------------------------------------
+// Helpers
+// -----------------------------------------------------------------------------
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn read_scaled(path: &Path, divisor: f64) -> Option<f64> {
+    read_trimmed(path)?.parse::<f64>().ok().map(|v| v / divisor)
+}
+
+fn sorted_entries(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.file_name().to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// hwmonN is numbered in probe order, which changes between boots. The parent bus
+/// device ("0000:00:18.3", "coretemp.0") does not; virtual chips (acpitz) have none.
+/// Some chips hang off a class device numbered in probe order too ("nvme0"), so
+/// those are named after the device below it, the NVMe controller's PCI address.
+fn chip_id(chip: &str, dir: &Path) -> String {
+    let mut device = dir.join("device");
+    if is_class_device(&device) {
+        device = device.join("device");
+    }
+    let device = fs::canonicalize(device)
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()));
+    match device {
+        Some(device) => format!("{}/{}", chip, device),
+        None => chip.to_string(),
+    }
+}
+
+/// Class devices ("nvme0" in /sys/class/nvme) have a `subsystem` link into /sys/class,
+/// bus devices into /sys/bus.
+fn is_class_device(device: &Path) -> bool {
+    fs::canonicalize(device.join("subsystem")).is_ok_and(|p| p.starts_with("/sys/class"))
+}
+
+/// "temp1_input" → (temperature kind, "temp1"); "power1_average" counts as the power input.
+fn split_input(file: &str) -> Option<(InputKind, &str)> {
+    let base = file.strip_suffix("_input").or_else(|| file.strip_suffix("_average"))?;
+    let kind = INPUT_KINDS
+        .iter()
+        .find(|(prefix, ..)| base.strip_prefix(prefix).is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())))?;
+    Some((*kind, base))
+}
+
+// -----------------------------------------------------------------------------
+// Reader
+// -----------------------------------------------------------------------------
+
+/// Keeps the previous energy counters so energy-only sources can be reported in watts.
+#[derive(Default)]
+pub struct HwmonReader {
+    previous_energy: HashMap<String, (Instant, f64)>,
+}
+
+impl HwmonReader {
+    pub fn read(&mut self) -> Vec<HwmonSensor> {
+        let mut sensors = Vec::new();
+        for entry in sorted_entries(Path::new(HWMON)) {
+            self.read_chip(&Path::new(HWMON).join(entry), &mut sensors);
+        }
+        self.read_rapl(&mut sensors);
+
+        // Two identical chips without a parent device would collide; number the later ones.
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for sensor in &mut sensors {
+            let count = seen.entry(sensor.id.clone()).or_insert(0);
+            *count += 1;
+            if *count > 1 {
+                sensor.id = format!("{}#{}", sensor.id, count);
+            }
+        }
+        sensors
+    }
+
+    fn read_chip(&mut self, dir: &Path, sensors: &mut Vec<HwmonSensor>) {
+        let chip = read_trimmed(&dir.join("name")).unwrap_or_else(|| "unknown".into());
+        let chip_id = chip_id(&chip, dir);
+        let files = sorted_entries(dir);
+
+        for file in &files {
+            // energyN_input counters (amd_energy, zenpower) become power readings.
+            if let Some(base) = file.strip_prefix("energy").and_then(|n| n.strip_suffix("_input")) {
+                let id = format!("{}/energy{}", chip_id, base);
+                let label = read_trimmed(&dir.join(format!("energy{}_label", base)));
+                if let Some(joules) = read_scaled(&dir.join(file), 1_000_000.0) {
+                    if let Some(watts) = self.watts(&id, joules, None) {
+                        sensors.push(sensor(id, &chip, "power", label.unwrap_or_else(|| format!("energy{}", base)), watts, "W"));
+                    }
+                }
+                continue;
+            }
+
+            let Some(((_, kind, unit, divisor), base)) = split_input(file) else { continue };
+            // power1_average and power1_input can both exist; keep one.
+            if file.ends_with("_average") && files.iter().any(|f| f == &format!("{}_input", base)) {
+                continue;
+            }
+            let Some(value) = read_scaled(&dir.join(file), divisor) else { continue };
+            let attr = |suffix: &str| read_scaled(&dir.join(format!("{}_{}", base, suffix)), divisor);
+
+            let mut reading = sensor(
+                format!("{}/{}", chip_id, base),
+                &chip,
+                kind,
+                read_trimmed(&dir.join(format!("{}_label", base))).unwrap_or_else(|| base.to_string()),
+                value,
+                unit,
+            );
+            reading.min = attr("min");
+            reading.max = attr("max").or_else(|| attr("cap"));
+            reading.critical = attr("crit");
+            sensors.push(reading);
+        }
+    }
+
+    /// intel-rapl:0 is "package-0", intel-rapl:0:0 its "core" subzone, and so on.
+    fn read_rapl(&mut self, sensors: &mut Vec<HwmonSensor>) {
+        let mut zone_names: HashMap<String, String> = HashMap::new();
+        for zone in sorted_entries(Path::new(POWERCAP)).into_iter().filter(|z| z.starts_with("intel-rapl:")) {
+            let dir: PathBuf = Path::new(POWERCAP).join(&zone);
+            let Some(name) = read_trimmed(&dir.join("name")) else { continue };
+            // Subzones are named after their parent package for a stable, readable ID.
+            let parent = zone.rsplit_once(':').map(|(p, _)| p.to_string()).filter(|p| p.contains(':'));
+            let path_name = match parent.and_then(|p| zone_names.get(&p)) {
+                Some(parent_name) => format!("{}/{}", parent_name, name),
+                None => name.clone(),
+            };
+            zone_names.insert(zone.clone(), path_name.clone());
+
+            let Some(joules) = read_scaled(&dir.join("energy_uj"), 1_000_000.0) else { continue };
+            let range = read_scaled(&dir.join("max_energy_range_uj"), 1_000_000.0);
+            let id = format!("rapl/{}", path_name);
+            if let Some(watts) = self.watts(&id, joules, range) {
+                sensors.push(sensor(id, "rapl", "power", path_name, watts, "W"));
+            }
+        }
+    }
+
+    /// Average power since the previous read of this counter; None on the first read.
+    fn watts(&mut self, id: &str, joules: f64, wrap_at: Option<f64>) -> Option<f64> {
+        let now = Instant::now();
+        let previous = self.previous_energy.insert(id.to_string(), (now, joules))?;
+        let seconds = now.duration_since(previous.0).as_secs_f64();
+        if seconds <= 0.0 {
+            return None;
+        }
+        let mut delta = joules - previous.1;
+        if delta < 0.0 {
+            delta += wrap_at?;
+        }
+        Some(delta / seconds)
+    }
+}
+
+fn sensor(id: String, chip: &str, kind: &str, label: String, value: f64, unit: &str) -> HwmonSensor {
+    HwmonSensor {
+        id,
+        chip: chip.to_string(),
+        kind: kind.to_string(),
+        label,
+        value,
+        unit: unit.to_string(),
+        min: None,
+        max: None,
+        critical: None,
+    }
+}
@@ -0,0 +1,909 @@
+// src/initramfs.rs
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+
+use crate::pacman_manager::{emit_progress, run_command_with_timeout};
+use crate::privileged::{read_file, run_as_root, write_file, FileChange};
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+const MKINITCPIO_CONF: &str = "/etc/mkinitcpio.conf";
+/// Sourced after mkinitcpio.conf (mkinitcpio >= 38), so assignments here win.
+const MKINITCPIO_DROPIN_DIR: &str = "/etc/mkinitcpio.conf.d";
+const MKINITCPIO_PRESET_DIR: &str = "/etc/mkinitcpio.d";
+const DRACUT_CONF: &str = "/etc/dracut.conf";
+const DRACUT_DROPIN_DIR: &str = "/etc/dracut.conf.d";
+/// Every dracut change we make goes into this drop-in instead of the user's files.
+const DRACUT_MANAGED_CONF: &str = "/etc/dracut.conf.d/90-linuxhub.conf";
+const MODULES_PATH: &str = "/usr/lib/modules";
+const HOOK_DIRS: &[&str] = &["/etc/initcpio/install", "/usr/lib/initcpio/install"];
+const DRACUT_MODULE_DIR: &str = "/usr/lib/dracut/modules.d";
+const COMPRESSORS: &[&str] = &["cat", "gzip", "bzip2", "lzma", "xz", "lzop", "lz4", "zstd"];
+const OP_DESC: &str = "Initramfs Generation";
+/// `mkinitcpio -P` / `dracut --regenerate-all` builds one image (plus fallback) per
+/// kernel, which with zstd -19 or many DKMS modules takes well over pacman's 5 minutes.
+const BUILD_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Hooks that have to run before `filesystems` to be useful; new ones are inserted there.
+const BEFORE_FILESYSTEMS: &[&str] = &[
+    "keyboard", "keymap", "consolefont", "sd-vconsole", "plymouth", "encrypt", "sd-encrypt",
+    "lvm2", "mdadm_udev", "resume", "btrfs",
+];
+/// Without these the initramfs cannot mount the root filesystem at all. Which set
+/// applies depends on the init: busybox with udev, or systemd (where base is optional).
+const REQUIRED_BUSYBOX_HOOKS: &[&str] = &["base", "udev", "filesystems"];
+const REQUIRED_SYSTEMD_HOOKS: &[&str] = &["systemd", "filesystems"];
+const REQUIRED_DRACUT_MODULES: &[&str] = &["base", "systemd", "rootfs-block", "kernel-modules"];
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InitramfsGenerator {
+    Mkinitcpio,
+    Dracut,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InitramfsKernel {
+    pub kernel_release: String,
+    pub pkgbase: Option<String>,
+    pub image_path: Option<String>,
+    /// The configuration changed after this image was built.
+    pub stale: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InitramfsConfig {
+    pub generator: InitramfsGenerator,
+    /// Files the effective configuration was read from, in the order they apply.
+    pub files: Vec<String>,
+    /// mkinitcpio MODULES, or dracut add_drivers.
+    pub modules: Vec<String>,
+    /// mkinitcpio HOOKS, or dracut add_dracutmodules.
+    pub hooks: Vec<String>,
+    /// dracut omit_dracutmodules; always empty for mkinitcpio.
+    pub omitted_hooks: Vec<String>,
+    /// None means the generator's default (zstd for both).
+    pub compression: Option<String>,
+    pub kernels: Vec<InitramfsKernel>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct InitramfsChange {
+    #[serde(default)]
+    pub add_hooks: Vec<String>,
+    #[serde(default)]
+    pub remove_hooks: Vec<String>,
+    #[serde(default)]
+    pub add_modules: Vec<String>,
+    #[serde(default)]
+    pub remove_modules: Vec<String>,
+    pub compression: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InitramfsChangePreview {
+    pub generator: InitramfsGenerator,
+    pub hooks: Vec<String>,
+    pub modules: Vec<String>,
+    pub warnings: Vec<String>,
+    pub files: Vec<FileChange>,
+    /// Originals are copied here before being overwritten.
+    pub backups: Vec<String>,
+    pub applied: bool,
+}
+
+/// A warning or error printed by mkinitcpio or dracut.
+#[derive(Debug, Serialize, Clone)]
+pub struct InitramfsDiagnostic {
+    /// "warning" or "error"
+    pub level: String,
+    pub message: String,
+    /// The module, hook or file the message is about, when it names one.
+    pub subject: Option<String>,
+    /// The hook that was running (mkinitcpio only).
+    pub hook: Option<String>,
+    /// The preset or image being built.
+    pub image: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InitramfsBuildResult {
+    pub success: bool,
+    pub message: String,
+    pub generator: InitramfsGenerator,
+    /// None when every kernel was rebuilt.
+    pub kernel_release: Option<String>,
+    pub command: String,
+    pub diagnostics: Vec<InitramfsDiagnostic>,
+}
+
+// -----------------------------------------------------------------------------
+// Detection
+// -----------------------------------------------------------------------------
+pub fn detect_generator() -> Option<InitramfsGenerator> {
+    if Path::new("/usr/bin/mkinitcpio").exists() && Path::new(MKINITCPIO_CONF).exists() {
+        Some(InitramfsGenerator::Mkinitcpio)
+    } else if Path::new("/usr/bin/dracut").exists() {
+        Some(InitramfsGenerator::Dracut)
+    } else {
+        None
+    }
+}
+
+fn config_files(generator: InitramfsGenerator) -> Vec<String> {
+    let (main, dropin_dir) = match generator {
+        InitramfsGenerator::Mkinitcpio => (MKINITCPIO_CONF, MKINITCPIO_DROPIN_DIR),
+        InitramfsGenerator::Dracut => (DRACUT_CONF, DRACUT_DROPIN_DIR),
+    };
+
+    let mut dropins: Vec<String> = fs::read_dir(dropin_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path().to_string_lossy().into_owned())
+                .filter(|p| p.ends_with(".conf"))
+                .collect()
+        })
+        .unwrap_or_default();
+    dropins.sort();
+
+    let mut files = Vec::new();
+    if Path::new(main).exists() {
+        files.push(main.to_string());
+    }
+    files.extend(dropins);
+    files
+}
+
+fn installed_kernels() -> Vec<(String, Option<String>)> {
+    let mut kernels: Vec<(String, Option<String>)> = fs::read_dir(MODULES_PATH)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().join("vmlinuz").exists())
+                .map(|e| {
+                    let pkgbase = fs::read_to_string(e.path().join("pkgbase")).ok().map(|s| s.trim().to_string());
+                    (e.file_name().to_string_lossy().into_owned(), pkgbase)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    kernels.sort();
+    kernels
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// -----------------------------------------------------------------------------
+// Parsing: mkinitcpio.conf is bash, dracut.conf is shell assignments
+// -----------------------------------------------------------------------------
+
+/// Drops a trailing `# comment` outside of quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('#', None) if i == 0 || line[..i].ends_with(char::is_whitespace) => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Line range (inclusive) and value of every active `KEY=...` assignment. Arrays
+/// may span several lines; the legacy `HOOKS="a b"` string form is accepted too.
+fn assignments(content: &str, key: &str) -> Vec<(usize, usize, Vec<String>)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut found = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = strip_comment(lines[i]).trim();
+        let Some(rest) = line.strip_prefix(key).and_then(|r| r.strip_prefix('=')) else {
+            i += 1;
+            continue;
+        };
+
+        let start = i;
+        let mut raw = rest.to_string();
+        if raw.starts_with('(') {
+            while !raw.contains(')') && i + 1 < lines.len() {
+                i += 1;
+                raw.push(' ');
+                raw.push_str(strip_comment(lines[i]).trim());
+            }
+            raw = raw.trim_start_matches('(').split(')').next().unwrap_or("").to_string();
+        }
+
+        let values = raw
+            .split_whitespace()
+            .map(|v| v.trim_matches(|c| c == '"' || c == '\'').to_string())
+            .filter(|v| !v.is_empty())
+            .collect();
+        found.push((start, i, values));
+        i += 1;
+    }
+    found
+}
+
+/// Replaces the last assignment of `key` (the one bash ends up with) or appends one.
+fn set_array(content: &str, key: &str, values: &[String]) -> String {
+    let new_line = format!("{}=({})", key, values.join(" "));
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+
+    match assignments(content, key).last() {
+        Some(&(start, end, _)) => {
+            lines.splice(start..=end, [new_line]);
+        }
+        None => lines.push(new_line),
+    }
+    lines.join("\n") + "\n"
+}
+
+fn set_scalar(content: &str, key: &str, value: &str) -> String {
+    let new_line = format!("{}=\"{}\"", key, value);
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+
+    match assignments(content, key).last() {
+        Some(&(start, end, _)) => {
+            lines.splice(start..=end, [new_line]);
+        }
+        None => lines.push(new_line),
+    }
+    lines.join("\n") + "\n"
+}
+
+/// dracut options accumulate over files with `+=` and reset with `=`.
+fn dracut_list(contents: &[(String, String)], key: &str) -> Vec<String> {
+    let mut values: Vec<String> = Vec::new();
+    for (_, content) in contents {
+        for line in content.lines() {
+            let line = strip_comment(line).trim();
+            let (append, rest) = if let Some(r) = line.strip_prefix(&format!("{}+=", key)) {
+                (true, r)
+            } else if let Some(r) = line.strip_prefix(&format!("{}=", key)) {
+                (false, r)
+            } else {
+                continue;
+            };
+            if !append {
+                values.clear();
+            }
+            for v in rest.trim_matches(|c| c == '"' || c == '\'').split_whitespace() {
+                if !values.iter().any(|x| x == v) {
+                    values.push(v.to_string());
+                }
+            }
+        }
+    }
+    values
+}
+
+fn dracut_scalar(contents: &[(String, String)], key: &str) -> Option<String> {
+    contents.iter().rev().find_map(|(_, content)| {
+        content.lines().rev().find_map(|line| {
+            let rest = strip_comment(line).trim().strip_prefix(key)?.strip_prefix('=')?;
+            Some(rest.trim_matches(|c| c == '"' || c == '\'').to_string())
+        })
+    })
+}
+
+fn read_all(files: &[String]) -> Result<Vec<(String, String)>, String> {
+    files.iter().map(|f| read_file(f).map(|c| (f.clone(), c))).collect()
+}
+
+/// The file whose assignment of `key` is effective, or the main config.
+fn owning_file<'a>(contents: &'a [(String, String)], key: &str) -> Option<&'a (String, String)> {
+    contents
+        .iter()
+        .rev()
+        .find(|(_, c)| !assignments(c, key).is_empty())
+        .or_else(|| contents.first())
+}
+
+fn effective_array(contents: &[(String, String)], key: &str) -> Vec<String> {
+    contents
+        .iter()
+        .rev()
+        .find_map(|(_, c)| assignments(c, key).pop())
+        .map(|(_, _, values)| values)
+        .unwrap_or_default()
+}
+
+fn load_config(generator: InitramfsGenerator) -> Result<InitramfsConfig, String> {
+    let files = config_files(generator);
+    let contents = read_all(&files)?;
+
+    let (modules, hooks, omitted_hooks, compression) = match generator {
+        InitramfsGenerator::Mkinitcpio => (
+            effective_array(&contents, "MODULES"),
+            effective_array(&contents, "HOOKS"),
+            Vec::new(),
+            effective_array(&contents, "COMPRESSION").into_iter().next(),
+        ),
+        InitramfsGenerator::Dracut => (
+            dracut_list(&contents, "add_drivers"),
+            dracut_list(&contents, "add_dracutmodules"),
+            dracut_list(&contents, "omit_dracutmodules"),
+            dracut_scalar(&contents, "compress"),
+        ),
+    };
+
+    let config_changed = files.iter().filter_map(|f| modified(f)).max();
+    let kernels = installed_kernels()
+        .into_iter()
+        .map(|(kernel_release, pkgbase)| {
+            let image_path = pkgbase.as_ref().map(|p| format!("/boot/initramfs-{}.img", p));
+            let built = image_path.as_deref().and_then(modified);
+            let stale = matches!((config_changed, built), (Some(c), Some(b)) if c > b);
+            InitramfsKernel { kernel_release, pkgbase, image_path, stale }
+        })
+        .collect();
+
+    Ok(InitramfsConfig { generator, files, modules, hooks, omitted_hooks, compression, kernels })
+}
+
+// -----------------------------------------------------------------------------
+// Validation
+// -----------------------------------------------------------------------------
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c))
+}
+
+fn hook_exists(generator: InitramfsGenerator, hook: &str) -> bool {
+    match generator {
+        InitramfsGenerator::Mkinitcpio => HOOK_DIRS.iter().any(|dir| Path::new(dir).join(hook).exists()),
+        InitramfsGenerator::Dracut => fs::read_dir(DRACUT_MODULE_DIR)
+            .map(|entries| {
+                entries.filter_map(|e| e.ok()).any(|e| {
+                    // Directories are named "90crypt", "01systemd-initrd", ...
+                    let name = e.file_name().to_string_lossy().into_owned();
+                    name.get(2..) == Some(hook)
+                })
+            })
+            .unwrap_or(false),
+    }
+}
+
+/// Modules may be built in, so this only checks the running kernel and only warns.
+fn module_exists(module: &str) -> bool {
+    std::process::Command::new("modinfo")
+        .arg(module)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(true)
+}
+
+fn root_is_encrypted() -> bool {
+    fs::read_to_string("/proc/cmdline")
+        .map(|c| c.contains("cryptdevice=") || c.contains("rd.luks."))
+        .unwrap_or(false)
+}
+
+/// The first hook the init in `hooks` needs but does not have.
+fn missing_required_hook(hooks: &[String]) -> Option<&'static str> {
+    let required = if hooks.iter().any(|h| h == "systemd") { REQUIRED_SYSTEMD_HOOKS } else { REQUIRED_BUSYBOX_HOOKS };
+    required.iter().find(|r| !hooks.iter().any(|h| h == *r)).copied()
+}
+
+fn check_change(
+    generator: InitramfsGenerator,
+    change: &InitramfsChange,
+    hooks: &[String],
+) -> Result<Vec<String>, String> {
+    let mut warnings = Vec::new();
+
+    for name in change.add_hooks.iter().chain(&change.add_modules).chain(&change.remove_hooks).chain(&change.remove_modules) {
+        if !valid_name(name) {
+            return Err(format!("Invalid name '{}'", name));
+        }
+    }
+
+    match generator {
+        InitramfsGenerator::Mkinitcpio => {
+            // Only changes are judged; a configuration that already lacks a hook stays editable.
+            let mut result = hooks.to_vec();
+            edit_list(&mut result, &change.add_hooks, &change.remove_hooks);
+            if missing_required_hook(hooks).is_none() {
+                if let Some(hook) = missing_required_hook(&result) {
+                    return Err(format!(
+                        "Refusing this change, without the '{}' hook the initramfs could not mount the root filesystem.",
+                        hook
+                    ));
+                }
+            }
+        }
+        InitramfsGenerator::Dracut => {
+            if let Some(module) = change.remove_hooks.iter().find(|m| REQUIRED_DRACUT_MODULES.contains(&m.as_str())) {
+                return Err(format!("Refusing to remove '{}', the initramfs could not mount the root filesystem.", module));
+            }
+        }
+    }
+    for hook in &change.remove_hooks {
+        let is_crypt = matches!(hook.as_str(), "encrypt" | "sd-encrypt" | "crypt");
+        if is_crypt && root_is_encrypted() {
+            return Err(format!("Refusing to remove '{}', the root filesystem is encrypted.", hook));
+        }
+    }
+
+    for hook in &change.add_hooks {
+        if !hook_exists(generator, hook) {
+            return Err(format!("'{}' is not an installed {} hook.", hook, match generator {
+                InitramfsGenerator::Mkinitcpio => "mkinitcpio",
+                InitramfsGenerator::Dracut => "dracut module",
+            }));
+        }
+    }
+
+    if generator == InitramfsGenerator::Mkinitcpio {
+        // busybox (udev) and systemd hooks have different names for the same job.
+        let systemd_based = hooks.iter().any(|h| h == "systemd") || change.add_hooks.iter().any(|h| h == "systemd");
+        for (busybox, systemd) in [("encrypt", "sd-encrypt"), ("keymap", "sd-vconsole"), ("udev", "systemd")] {
+            if systemd_based && change.add_hooks.iter().any(|h| h == busybox) {
+                warnings.push(format!("'{}' does not work with the systemd hook, use '{}' instead.", busybox, systemd));
+            }
+            if !systemd_based && change.add_hooks.iter().any(|h| h == systemd) && systemd != "systemd" {
+                warnings.push(format!("'{}' needs the systemd hook, use '{}' instead.", systemd, busybox));
+            }
+        }
+    }
+
+    for module in &change.add_modules {
+        if !module_exists(module) {
+            warnings.push(format!("Module '{}' was not found for the running kernel.", module));
+        }
+    }
+
+    if let Some(c) = &change.compression {
+        if !COMPRESSORS.contains(&c.as_str()) {
+            return Err(format!("Unknown compression '{}', expected one of: {}", c, COMPRESSORS.join(", ")));
+        }
+        if c != "cat" && !Path::new("/usr/bin").join(c).exists() {
+            return Err(format!("Compression '{}' needs /usr/bin/{}, which is not installed.", c, c));
+        }
+    }
+
+    Ok(warnings)
+}
+
+// -----------------------------------------------------------------------------
+// Changes: computed once for the preview and again when applying
+// -----------------------------------------------------------------------------
+/// Resulting hooks, modules and file edits.
+type PlannedEdit = (Vec<String>, Vec<String>, Vec<FileChange>);
+
+fn add_hook(hooks: &mut Vec<String>, hook: &str) {
+    if hooks.iter().any(|h| h == hook) {
+        return;
+    }
+    let anchor = if BEFORE_FILESYSTEMS.contains(&hook) { "filesystems" } else { "fsck" };
+    match hooks.iter().position(|h| h == anchor) {
+        Some(i) => hooks.insert(i, hook.to_string()),
+        None => hooks.push(hook.to_string()),
+    }
+}
+
+fn edit_list(list: &mut Vec<String>, add: &[String], remove: &[String]) {
+    list.retain(|v| !remove.contains(v));
+    for v in add {
+        if !list.contains(v) {
+            list.push(v.clone());
+        }
+    }
+}
+
+fn plan_change(change: &InitramfsChange) -> Result<InitramfsChangePreview, String> {
+    let generator = detect_generator().ok_or("Neither mkinitcpio nor dracut is installed.")?;
+    let contents = read_all(&config_files(generator))?;
+    let config = load_config(generator)?;
+    let warnings = check_change(generator, change, &config.hooks)?;
+
+    let (hooks, modules, files) = match generator {
+        InitramfsGenerator::Mkinitcpio => plan_mkinitcpio(&contents, change),
+        InitramfsGenerator::Dracut => plan_dracut(&contents, change)?,
+    };
+    let files: Vec<FileChange> = files.into_iter().filter(|f| !f.is_empty()).collect();
+    // Timestamped, so a second change does not overwrite the backup of the original file.
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    let backups = files
+        .iter()
+        .filter(|f| !f.original.is_empty())
+        .map(|f| format!("{}.linuxhub-{}.bak", f.path, stamp))
+        .collect();
+
+    Ok(InitramfsChangePreview { generator, hooks, modules, warnings, files, backups, applied: false })
+}
+
+fn plan_mkinitcpio(
+    contents: &[(String, String)],
+    change: &InitramfsChange,
+) -> PlannedEdit {
+    let mut hooks = effective_array(contents, "HOOKS");
+    hooks.retain(|h| !change.remove_hooks.contains(h));
+    for hook in &change.add_hooks {
+        add_hook(&mut hooks, hook);
+    }
+    let mut modules = effective_array(contents, "MODULES");
+    edit_list(&mut modules, &change.add_modules, &change.remove_modules);
+
+    // Each key is written where its effective assignment lives, so a drop-in
+    // overriding HOOKS is edited instead of silently shadowing our change.
+    let mut edited: Vec<(String, String, String)> = contents
+        .iter()
+        .map(|(path, content)| (path.clone(), content.clone(), content.clone()))
+        .collect();
+    let mut edit = |key: &str, apply: &dyn Fn(&str) -> String| {
+        if let Some((path, _)) = owning_file(contents, key) {
+            if let Some(entry) = edited.iter_mut().find(|(p, _, _)| p == path) {
+                entry.2 = apply(&entry.2);
+            }
+        }
+    };
+
+    edit("HOOKS", &|c| set_array(c, "HOOKS", &hooks));
+    edit("MODULES", &|c| set_array(c, "MODULES", &modules));
+    if let Some(compression) = &change.compression {
+        edit("COMPRESSION", &|c| set_scalar(c, "COMPRESSION", compression));
+    }
+
+    let files = edited
+        .into_iter()
+        .map(|(path, original, updated)| FileChange::new(&path, original, updated))
+        .collect();
+    (hooks, modules, files)
+}
+
+/// Hooks map to dracut modules and modules to drivers. Everything is written to
+/// our own drop-in: adding re-enables anything we omitted before, removing omits.
+fn plan_dracut(
+    contents: &[(String, String)],
+    change: &InitramfsChange,
+) -> Result<PlannedEdit, String> {
+    let original = contents
+        .iter()
+        .find(|(p, _)| p == DRACUT_MANAGED_CONF)
+        .map(|(_, c)| c.clone())
+        .unwrap_or_default();
+    let managed = [(DRACUT_MANAGED_CONF.to_string(), original.clone())];
+
+    let mut add_modules = dracut_list(&managed, "add_dracutmodules");
+    let mut omit_modules = dracut_list(&managed, "omit_dracutmodules");
+    let mut add_drivers = dracut_list(&managed, "add_drivers");
+    let mut omit_drivers = dracut_list(&managed, "omit_drivers");
+    edit_list(&mut add_modules, &change.add_hooks, &change.remove_hooks);
+    edit_list(&mut omit_modules, &change.remove_hooks, &change.add_hooks);
+    edit_list(&mut add_drivers, &change.add_modules, &change.remove_modules);
+    edit_list(&mut omit_drivers, &change.remove_modules, &change.add_modules);
+    let compress = change.compression.clone().or_else(|| dracut_scalar(&managed, "compress"));
+
+    let mut updated = String::from("# Managed by LinuxHub, changes here may be overwritten.\n");
+    for (key, values) in [
+        ("add_dracutmodules", &add_modules),
+        ("omit_dracutmodules", &omit_modules),
+        ("add_drivers", &add_drivers),
+        ("omit_drivers", &omit_drivers),
+    ] {
+        if !values.is_empty() {
+            updated.push_str(&format!("{}+=\" {} \"\n", key, values.join(" ")));
+        }
+    }
+    if let Some(c) = compress {
+        updated.push_str(&format!("compress=\"{}\"\n", c));
+    }
+    if original.is_empty() && updated.lines().count() == 1 {
+        updated.clear();
+    }
+
+    // Report the effective lists with our drop-in applied last.
+    let mut all: Vec<(String, String)> = contents.iter().filter(|(p, _)| p != DRACUT_MANAGED_CONF).cloned().collect();
+    all.push((DRACUT_MANAGED_CONF.to_string(), updated.clone()));
+    let mut hooks = dracut_list(&all, "add_dracutmodules");
+    hooks.retain(|h| !dracut_list(&all, "omit_dracutmodules").contains(h));
+    let mut modules = dracut_list(&all, "add_drivers");
+    modules.retain(|m| !dracut_list(&all, "omit_drivers").contains(m));
+
+    Ok((hooks, modules, vec![FileChange::new(DRACUT_MANAGED_CONF, original, updated)]))
+}
+
+// -----------------------------------------------------------------------------
+// Regeneration
+// -----------------------------------------------------------------------------
+fn build_command(generator: InitramfsGenerator, kernel_release: Option<&str>) -> Result<Vec<String>, String> {
+    let Some(release) = kernel_release else {
+        return Ok(match generator {
+            InitramfsGenerator::Mkinitcpio => vec!["mkinitcpio".into(), "-P".into()],
+            InitramfsGenerator::Dracut => vec!["dracut".into(), "--force".into(), "--regenerate-all".into()],
+        });
+    };
+
+    let pkgbase = installed_kernels()
+        .into_iter()
+        .find(|(r, _)| r == release)
+        .ok_or_else(|| format!("Kernel {} is not installed.", release))?
+        .1;
+
+    Ok(match (generator, pkgbase) {
+        (InitramfsGenerator::Mkinitcpio, Some(pkgbase)) => {
+            if !Path::new(MKINITCPIO_PRESET_DIR).join(format!("{}.preset", pkgbase)).exists() {
+                return Err(format!("No mkinitcpio preset for {}.", pkgbase));
+            }
+            vec!["mkinitcpio".into(), "-p".into(), pkgbase]
+        }
+        (InitramfsGenerator::Mkinitcpio, None) => {
+            return Err(format!("Kernel {} has no pkgbase, cannot find its preset.", release))
+        }
+        // Arch names images after the package, not the release dracut defaults to.
+        (InitramfsGenerator::Dracut, Some(pkgbase)) => vec![
+            "dracut".into(),
+            "--force".into(),
+            "--kver".into(),
+            release.into(),
+            format!("/boot/initramfs-{}.img", pkgbase),
+        ],
+        (InitramfsGenerator::Dracut, None) => {
+            vec!["dracut".into(), "--force".into(), "--kver".into(), release.into()]
+        }
+    })
+}
+
+/// Picks out the text between the first pair of single quotes.
+fn quoted_subject(message: &str) -> Option<String> {
+    let start = message.find('\'')? + 1;
+    let len = message[start..].find('\'')?;
+    Some(message[start..start + len].to_string())
+}
+
+/// mkinitcpio: "==> WARNING: Possibly missing firmware for module: 'qla2xxx'",
+/// "==> ERROR: module not found: 'foo'", with "-> Running build hook: [kms]" and
+/// "==> Building image from preset: /etc/mkinitcpio.d/linux.preset: 'default'" as context.
+/// dracut: "dracut[W]: ...", "dracut[E]: ...", "dracut-install: ERROR: ...".
+fn parse_diagnostics(lines: &[String]) -> Vec<InitramfsDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut hook: Option<String> = None;
+    let mut image: Option<String> = None;
+
+    for line in lines {
+        let line = line.trim();
+
+        if let Some(rest) = line.strip_prefix("-> Running build hook:") {
+            hook = Some(rest.trim().trim_matches(|c| c == '[' || c == ']').to_string());
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("==> Building image from preset:") {
+            image = Some(rest.trim().to_string());
+            hook = None;
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("dracut: *** Creating image file '") {
+            image = rest.strip_suffix("' ***").map(|s| s.to_string());
+            continue;
+        }
+
+        let parsed = [
+            ("==> WARNING:", "warning"),
+            ("==> ERROR:", "error"),
+            ("dracut[W]:", "warning"),
+            ("dracut[E]:", "error"),
+            ("dracut-install: ERROR:", "error"),
+            ("dracut-install: WARNING:", "warning"),
+        ]
+        .iter()
+        .find_map(|(prefix, level)| line.strip_prefix(prefix).map(|m| (*level, m.trim())));
+
+        if let Some((level, message)) = parsed {
+            diagnostics.push(InitramfsDiagnostic {
+                level: level.to_string(),
+                message: message.to_string(),
+                subject: quoted_subject(message),
+                hook: hook.clone(),
+                image: image.clone(),
+            });
+        }
+    }
+    diagnostics
+}
+
+// -----------------------------------------------------------------------------
+// Tauri commands
+// -----------------------------------------------------------------------------
+#[tauri::command]
+pub async fn get_initramfs_config() -> Result<InitramfsConfig, String> {
+    // read_file falls back to pkexec for root-only drop-ins.
+    tokio::task::spawn_blocking(|| {
+        let generator = detect_generator().ok_or("Neither mkinitcpio nor dracut is installed.")?;
+        load_config(generator)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn preview_initramfs_change(change: InitramfsChange) -> Result<InitramfsChangePreview, String> {
+    tokio::task::spawn_blocking(move || plan_change(&change))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Writes the configuration only; call `regenerate_initramfs` to rebuild the images.
+#[tauri::command]
+pub async fn apply_initramfs_change(change: InitramfsChange) -> Result<InitramfsChangePreview, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut plan = plan_change(&change)?;
+
+        for (file, backup) in plan.files.iter().filter(|f| !f.original.is_empty()).zip(&plan.backups) {
+            run_as_root("cp", &["-a", &file.path, backup])?;
+        }
+        for file in &plan.files {
+            write_file(&file.path, &file.updated)?;
+        }
+
+        plan.applied = true;
+        Ok(plan)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Rebuilds the initramfs for one kernel release, or for every kernel when none is given.
+#[tauri::command]
+pub async fn regenerate_initramfs(
+    app_handle: AppHandle,
+    kernel_release: Option<String>,
+) -> Result<InitramfsBuildResult, String> {
+    let generator = detect_generator().ok_or("Neither mkinitcpio nor dracut is installed.")?;
+    let command = build_command(generator, kernel_release.as_deref())?;
+    let args: Vec<&str> = command.iter().map(String::as_str).collect();
+
+    emit_progress(&app_handle, OP_DESC, &format!("Starting {}...", OP_DESC));
+    let output = run_command_with_timeout("pkexec", &args, &app_handle, OP_DESC, BUILD_TIMEOUT).await?;
+
+    if matches!(output.exit_code, Some(126) | Some(127)) {
+        return Err(format!(
+            "Root permission denied or cancelled by user. (Exit Code: {})",
+            output.exit_code.unwrap_or(-1)
+        ));
+    }
+
+    let diagnostics = parse_diagnostics(&output.lines);
+    let errors = diagnostics.iter().filter(|d| d.level == "error").count();
+    let warnings = diagnostics.len() - errors;
+    let success = output.exit_code == Some(0) && errors == 0;
+
+    let message = if success {
+        format!("Initramfs regenerated with {} warning(s).", warnings)
+    } else {
+        format!(
+            "Initramfs generation failed (Exit Code: {}) with {} error(s).",
+            output.exit_code.unwrap_or(-1),
+            errors
+        )
+    };
+    emit_progress(&app_handle, OP_DESC, &message);
+
+    Ok(InitramfsBuildResult {
+        success,
+        message,
+        generator,
+        kernel_release,
+        command: command.join(" "),
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn files(contents: &[&str]) -> Vec<(String, String)> {
+        contents.iter().enumerate().map(|(i, c)| (format!("file{}", i), c.to_string())).collect()
+    }
+
+    #[test]
+    fn comments_are_stripped_outside_quotes() {
+        let cases = [
+            ("HOOKS=(base udev) # default", "HOOKS=(base udev) "),
+            ("# HOOKS=(base)", ""),
+            ("COMPRESSION=\"zstd # fast\"", "COMPRESSION=\"zstd # fast\""),
+            ("MODULES=(i915#x)", "MODULES=(i915#x)"),
+            ("MODULES=()", "MODULES=()"),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(strip_comment(line), expected, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn arrays_are_read_in_every_form() {
+        let cases: [(&str, &[&str]); 7] = [
+            ("HOOKS=(base udev autodetect filesystems)\n", &["base", "udev", "autodetect", "filesystems"]),
+            ("HOOKS=\"base udev filesystems\"\n", &["base", "udev", "filesystems"]),
+            ("HOOKS=(base\n  udev # comment\n  filesystems)\n", &["base", "udev", "filesystems"]),
+            ("#HOOKS=(base)\nHOOKS=(systemd filesystems)\n", &["systemd", "filesystems"]),
+            ("HOOKS=(base)\nHOOKS=(systemd)\n", &["systemd"]),
+            ("HOOKS=()\n", &[]),
+            ("MODULES=(i915)\n", &[]),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(effective_array(&files(&[content]), "HOOKS"), strings(expected), "{:?}", content);
+        }
+        // A drop-in assignment wins over the main file.
+        let contents = files(&["HOOKS=(base udev filesystems)\n", "HOOKS=(systemd filesystems)\n"]);
+        assert_eq!(effective_array(&contents, "HOOKS"), strings(&["systemd", "filesystems"]));
+        assert_eq!(owning_file(&contents, "HOOKS").map(|(p, _)| p.as_str()), Some("file1"));
+        assert_eq!(owning_file(&contents, "FILES").map(|(p, _)| p.as_str()), Some("file0"));
+    }
+
+    #[test]
+    fn assignments_are_rewritten_in_place() {
+        let values = strings(&["base", "udev", "filesystems"]);
+        let cases = [
+            ("MODULES=()\nHOOKS=(base\n  filesystems)\nFILES=()\n", "MODULES=()\nHOOKS=(base udev filesystems)\nFILES=()\n"),
+            ("HOOKS=(a)\n# keep\nHOOKS=(b)\n", "HOOKS=(a)\n# keep\nHOOKS=(base udev filesystems)\n"),
+            ("#HOOKS=(base)\n", "#HOOKS=(base)\nHOOKS=(base udev filesystems)\n"),
+            ("", "HOOKS=(base udev filesystems)\n"),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(set_array(content, "HOOKS", &values), expected, "{:?}", content);
+        }
+        assert_eq!(set_scalar("#COMPRESSION=\"xz\"\n", "COMPRESSION", "zstd"), "#COMPRESSION=\"xz\"\nCOMPRESSION=\"zstd\"\n");
+        assert_eq!(set_scalar("COMPRESSION=xz # old\n", "COMPRESSION", "zstd"), "COMPRESSION=\"zstd\"\n");
+    }
+
+    #[test]
+    fn dracut_options_accumulate_and_reset() {
+        let contents = files(&[
+            "add_dracutmodules+=\" crypt \"\n",
+            "add_dracutmodules+=\" lvm crypt \" # again\n",
+            "# add_dracutmodules=\"\"\n",
+        ]);
+        assert_eq!(dracut_list(&contents, "add_dracutmodules"), strings(&["crypt", "lvm"]));
+        let reset = files(&["add_dracutmodules+=\" crypt \"\n", "add_dracutmodules=\" lvm \"\n"]);
+        assert_eq!(dracut_list(&reset, "add_dracutmodules"), strings(&["lvm"]));
+        assert!(dracut_list(&contents, "omit_dracutmodules").is_empty());
+
+        let scalars = files(&["compress=\"xz\"\n", "compress='zstd'\n"]);
+        assert_eq!(dracut_scalar(&scalars, "compress").as_deref(), Some("zstd"));
+        assert_eq!(dracut_scalar(&scalars, "hostonly"), None);
+    }
+
+    #[test]
+    fn required_hooks_follow_the_init() {
+        let cases: [(&[&str], Option<&str>); 6] = [
+            (&["base", "udev", "autodetect", "filesystems", "fsck"], None),
+            (&["systemd", "autodetect", "filesystems"], None),
+            (&["base", "systemd", "filesystems"], None),
+            (&["base", "autodetect", "filesystems"], Some("udev")),
+            (&["systemd", "autodetect"], Some("filesystems")),
+            (&[], Some("base")),
+        ];
+        for (hooks, expected) in cases {
+            assert_eq!(missing_required_hook(&strings(hooks)), expected, "{:?}", hooks);
+        }
+    }
+
+    #[test]
+    fn new_hooks_go_before_filesystems_or_fsck() {
+        let mut hooks = strings(&["base", "udev", "block", "filesystems", "fsck"]);
+        add_hook(&mut hooks, "encrypt");
+        add_hook(&mut hooks, "resume");
+        add_hook(&mut hooks, "shutdown");
+        add_hook(&mut hooks, "udev");
+        assert_eq!(hooks, strings(&["base", "udev", "block", "encrypt", "resume", "filesystems", "shutdown", "fsck"]));
+    }
+}
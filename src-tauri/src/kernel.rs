@@ -0,0 +1,477 @@
+// src/kernel.rs
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+use sysinfo::System;
+use tauri::AppHandle;
+
+use crate::kernel_lifecycle::{apply_lifecycle, load_lifecycle_data};
+use crate::model::{DkmsModuleStatus, InstallableKernel, InstalledKernel, KernelInfo, KernelOperationResult};
+use crate::pacman_db::{parse_info_output, run_pacman, strip_version_constraint, PackageRecord};
+use crate::pacman_manager::{emit_progress, run_command_with_timeout};
+
+const MODULES_PATH: &str = "/usr/lib/modules";
+/// File every Arch kernel package ships next to its module tree.
+const KERNEL_IMAGE_REGEX: &str = "^usr/lib/modules/[^/]+/vmlinuz$";
+/// Kernel transactions run the initramfs and DKMS hooks; killing pacman halfway can
+/// leave a kernel without an initramfs, so allow a download plus initramfs.rs's build time.
+const TRANSACTION_TIMEOUT: Duration = Duration::from_secs(45 * 60);
+
+#[tauri::command]
+pub async fn get_system_kernels(app_handle: AppHandle) -> Result<String, String> {
+    let running_kernel = get_running_kernel_version();
+    let installed_kernels = get_installed_kernels(&running_kernel).await.map_err(|e| format!("Failed to get installed kernels: {}", e))?;
+    let installable_kernels = get_installable_kernels_from_repos().await.map_err(|e| format!("Failed to get installable kernels: {}", e))?;
+
+    let mut result = KernelInfo {
+        running_kernel,
+        running_kernel_lifecycle: "unknown".to_string(),
+        running_kernel_eol: false,
+        running_kernel_eol_date: None,
+        recommended_kernel: None,
+        lifecycle_data_updated: String::new(),
+        installed_kernels,
+        installable_kernels,
+    };
+    apply_lifecycle(&mut result, &load_lifecycle_data(&app_handle));
+
+    // Serialize the final struct to a JSON string
+    serde_json::to_string_pretty(&result)
+        .map_err(|e| format!("Failed to serialize kernel info to JSON: {}", e))
+}
+
+fn get_running_kernel_version() -> String {
+    System::kernel_version().unwrap_or_else(|| "<unknown>".to_owned())
+}
+
+async fn run_command(cmd: &str, args: &[&str]) -> Result<String, String> {
+    let output = tokio::process::Command::new(cmd)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute command '{}': {}", cmd, e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "Command failed: {} {}", 
+            cmd, 
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    }
+}
+
+/// Resolves every directory in `/usr/lib/modules` to the package that installed it.
+///
+/// Arch kernel packages write their `pkgbase` into the modules directory and own
+/// the `vmlinuz` image next to it, so both are used instead of guessing from the
+/// directory name. A directory whose image nobody owns is left over from a removed
+/// kernel (typically DKMS-built modules) and reported as orphaned.
+async fn get_installed_kernels(running_release: &str) -> Result<Vec<InstalledKernel>, String> {
+    let entries = std::fs::read_dir(MODULES_PATH)
+        .map_err(|e| format!("Failed to read {}: {}", MODULES_PATH, e))?;
+
+    let mut installed_kernels = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let release = entry.file_name().to_string_lossy().into_owned();
+
+        let pkgbase = std::fs::read_to_string(path.join("pkgbase"))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        let owner = package_owning(&path.join("vmlinuz")).await;
+
+        let (name, version, orphaned) = match &owner {
+            Some(pkg) => {
+                let version = package_version(pkg).await.unwrap_or_else(|| release.clone());
+                (pkg.clone(), version, false)
+            }
+            None => (pkgbase.clone().unwrap_or_else(|| release.clone()), release.clone(), true),
+        };
+
+        // `build` is a symlink into the headers package when it is installed.
+        let headers_owner = package_owning(&path.join("build")).await;
+        let headers_installed = headers_owner.is_some();
+        let headers_package = headers_owner
+            .or_else(|| pkgbase.as_ref().map(|base| format!("{}-headers", base)));
+
+        installed_kernels.push(InstalledKernel {
+            flavor: kernel_flavor(pkgbase.as_deref().unwrap_or(&name)),
+            name,
+            version,
+            pkgbase,
+            kernel_release: release.clone(),
+            headers_package,
+            headers_installed,
+            running: release == running_release,
+            orphaned,
+        });
+    }
+
+    installed_kernels.sort_by(|a, b| b.running.cmp(&a.running).then_with(|| a.name.cmp(&b.name)));
+    Ok(installed_kernels)
+}
+
+/// "linux" → "default", "linux-lts" → "lts", "linux-rt-lts" → "rt", "linux-zen" → "zen"
+fn kernel_flavor(pkgbase: &str) -> String {
+    let suffix = pkgbase
+        .strip_prefix("linux-")
+        .or_else(|| pkgbase.strip_prefix("linux"))
+        .unwrap_or(pkgbase);
+
+    // Manjaro names its kernels by series ("linux66"), those are plain kernels.
+    if suffix.is_empty() || suffix.chars().all(|c| c.is_ascii_digit()) {
+        "default".to_string()
+    } else if suffix == "rt" || suffix.starts_with("rt-") {
+        "rt".to_string()
+    } else if suffix.starts_with("lts") {
+        "lts".to_string()
+    } else {
+        suffix.to_string()
+    }
+}
+
+/// `pacman -Qqo <path>`: the package owning a file, if any.
+async fn package_owning(path: &Path) -> Option<String> {
+    if !path.exists() && !path.is_symlink() {
+        return None;
+    }
+    let path = path.to_string_lossy();
+    run_command("pacman", &["-Qqo", &path]).await.ok()
+        .and_then(|out| out.lines().next().map(|s| s.to_string()))
+}
+
+/// `pacman -Q <pkg>` → "6.6.1.arch1-1"
+async fn package_version(package: &str) -> Option<String> {
+    run_command("pacman", &["-Q", package]).await.ok()
+        .and_then(|out| out.split_whitespace().nth(1).map(|s| s.to_string()))
+}
+
+/// Kernels available in any repository configured in pacman.conf.
+///
+/// A kernel is a package shipping `usr/lib/modules/<release>/vmlinuz`, found
+/// through the files database (`pacman -F`). When that database has never been
+/// synced, fall back to packages that come with a matching `-headers` package
+/// in the same repository and depend on kmod and an initramfs generator, which
+/// firmware and tool packages do not.
+async fn get_installable_kernels_from_repos() -> Result<Vec<InstallableKernel>, String> {
+    let mut candidates = kernels_from_files_db().await;
+    let from_files_db = !candidates.is_empty();
+    if !from_files_db {
+        candidates = kernels_from_headers_pairs().await?;
+    }
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut records = sync_info(&candidates).await;
+    if !from_files_db {
+        records.retain(looks_like_kernel);
+    }
+
+    let mut installable_list: Vec<InstallableKernel> = records
+        .into_iter()
+        .map(|record| InstallableKernel {
+            flavor: kernel_flavor(&record.name),
+            repository: record.repository.unwrap_or_default(),
+            package_name: record.name,
+            version: record.version,
+            description: record.description,
+            series: None,
+            lifecycle: "unknown".to_string(),
+            eol_date: None,
+            recommended: false,
+        })
+        .collect();
+
+    installable_list.sort_by(|a, b| a.repository.cmp(&b.repository).then_with(|| a.package_name.cmp(&b.package_name)));
+    Ok(installable_list)
+}
+
+/// `pacman -Si` for all candidates in one call. A package that vanished from its
+/// repository since the last sync fails the whole call; then each one is queried
+/// on its own and the failing ones are skipped.
+async fn sync_info(candidates: &[(String, String)]) -> Vec<PackageRecord> {
+    let qualified: Vec<String> = candidates.iter().map(|(repo, name)| format!("{}/{}", repo, name)).collect();
+    let args: Vec<&str> = std::iter::once("-Si").chain(qualified.iter().map(String::as_str)).collect();
+    if let Ok(output) = run_pacman(&args).await {
+        return parse_info_output(&output);
+    }
+
+    let mut records = Vec::new();
+    for package in &qualified {
+        if let Ok(output) = run_pacman(&["-Si", package]).await {
+            records.extend(parse_info_output(&output));
+        }
+    }
+    records
+}
+
+/// Arch and Manjaro kernel packages depend on kmod and on an initramfs generator
+/// (the `initramfs` virtual package, or mkinitcpio directly on older packages).
+fn looks_like_kernel(record: &PackageRecord) -> bool {
+    let depends = |name: &str| record.depends_on.iter().any(|d| strip_version_constraint(d) == name);
+    depends("kmod") && ["initramfs", "mkinitcpio", "dracut", "booster"].iter().any(|g| depends(g))
+}
+
+// --machinereadable: "repo\0pkgname\0pkgver\0path"
+async fn kernels_from_files_db() -> Vec<(String, String)> {
+    let output = match run_pacman(&["-F", "--regex", "--machinereadable", KERNEL_IMAGE_REGEX]).await {
+        Ok(o) => o,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut kernels: Vec<(String, String)> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            let repo = fields.next()?;
+            let name = fields.next()?;
+            Some((repo.to_string(), name.to_string()))
+        })
+        .collect();
+    kernels.sort();
+    kernels.dedup();
+    kernels
+}
+
+// "core linux 6.9.1.arch1-1 [installed]"
+async fn kernels_from_headers_pairs() -> Result<Vec<(String, String)>, String> {
+    let output = run_pacman(&["-Sl"]).await?;
+    let packages: HashSet<(&str, &str)> = output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some((parts.next()?, parts.next()?))
+        })
+        .collect();
+
+    let mut kernels: Vec<(String, String)> = packages
+        .iter()
+        .filter(|(_, name)| !name.ends_with("-headers"))
+        .filter(|(repo, name)| packages.contains(&(*repo, format!("{}-headers", name).as_str())))
+        .map(|(repo, name)| (repo.to_string(), name.to_string()))
+        .collect();
+    kernels.sort();
+    Ok(kernels)
+}
+
+// -----------------------------------------------------------------------------
+// Kernel install / remove
+// -----------------------------------------------------------------------------
+
+/// Installs a kernel package. When DKMS modules are registered the matching
+/// `-headers` package is installed too, and the per-module build status for the
+/// new kernel release is reported afterwards (pacman's DKMS hook builds them).
+#[tauri::command]
+pub async fn install_kernel(
+    app_handle: AppHandle,
+    package_name: String,
+) -> Result<KernelOperationResult, String> {
+    let registered_modules = dkms_status(None).await;
+    let headers_package = (!registered_modules.is_empty()).then(|| format!("{}-headers", package_name));
+
+    let mut args = vec!["pacman", "-S", "--needed", "--noconfirm", package_name.as_str()];
+    if let Some(headers) = &headers_package {
+        args.push(headers);
+        emit_progress(
+            &app_handle,
+            "Kernel Installation",
+            &format!("{} DKMS module(s) registered, adding {}", registered_modules.len(), headers),
+        );
+    }
+
+    run_command_with_timeout("pkexec", &args, &app_handle, "Kernel Installation", TRANSACTION_TIMEOUT).await?;
+
+    if package_version(&package_name).await.is_none() {
+        return Ok(KernelOperationResult {
+            success: false,
+            message: format!("{} was not installed.", package_name),
+            package_name,
+            headers_package,
+            kernel_release: None,
+            dkms_modules: Vec::new(),
+        });
+    }
+
+    let running = get_running_kernel_version();
+    let kernel_release = get_installed_kernels(&running)
+        .await?
+        .into_iter()
+        .find(|k| k.name == package_name)
+        .map(|k| k.kernel_release);
+
+    let dkms_modules = match &kernel_release {
+        Some(release) => dkms_modules_for_release(&registered_modules, release).await,
+        None => Vec::new(),
+    };
+    let failed: Vec<&str> = dkms_modules
+        .iter()
+        .filter(|m| m.status != "installed")
+        .map(|m| m.module.as_str())
+        .collect();
+
+    let message = if failed.is_empty() {
+        format!("{} installed.", package_name)
+    } else {
+        format!("{} installed, but DKMS modules are not built for it: {}", package_name, failed.join(", "))
+    };
+    emit_progress(&app_handle, "Kernel Installation", &message);
+
+    Ok(KernelOperationResult {
+        success: true,
+        message,
+        package_name,
+        headers_package,
+        kernel_release,
+        dkms_modules,
+    })
+}
+
+/// Removes a kernel package and its headers. Refuses to remove the running
+/// kernel or the last installed one, which would leave the system unbootable.
+/// After the running kernel was upgraded its modules directory is gone and it
+/// matches no installed release, so nothing is removed until a reboot.
+#[tauri::command]
+pub async fn remove_kernel(
+    app_handle: AppHandle,
+    package_name: String,
+) -> Result<KernelOperationResult, String> {
+    let running = get_running_kernel_version();
+    let installed: Vec<InstalledKernel> = get_installed_kernels(&running)
+        .await?
+        .into_iter()
+        .filter(|k| !k.orphaned)
+        .collect();
+
+    let target = installed
+        .iter()
+        .find(|k| k.name == package_name)
+        .ok_or_else(|| format!("{} is not an installed kernel.", package_name))?;
+
+    let running_modules = Path::new(MODULES_PATH).join(&running);
+    if !running_modules.is_dir() {
+        return Err(format!(
+            "The running kernel ({}) was upgraded or removed since boot; reboot before removing kernels.",
+            running
+        ));
+    }
+    let running_pkgbase = std::fs::read_to_string(running_modules.join("pkgbase"))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    if target.running || (running_pkgbase.is_some() && target.pkgbase == running_pkgbase) {
+        return Err(format!("{} is the running kernel and cannot be removed.", package_name));
+    }
+    if installed.len() <= 1 {
+        return Err(format!("{} is the last installed kernel and cannot be removed.", package_name));
+    }
+
+    let headers_package = target.headers_package.clone().filter(|_| target.headers_installed);
+    let kernel_release = Some(target.kernel_release.clone());
+
+    let mut args = vec!["pacman", "-Rns", "--noconfirm", package_name.as_str()];
+    if let Some(headers) = &headers_package {
+        args.push(headers);
+    }
+
+    run_command_with_timeout("pkexec", &args, &app_handle, "Kernel Removal", TRANSACTION_TIMEOUT).await?;
+
+    let success = package_version(&package_name).await.is_none();
+    let message = if success {
+        format!("{} removed.", package_name)
+    } else {
+        format!("{} could not be removed.", package_name)
+    };
+    emit_progress(&app_handle, "Kernel Removal", &message);
+
+    Ok(KernelOperationResult {
+        success,
+        message,
+        package_name,
+        headers_package,
+        kernel_release,
+        dkms_modules: Vec::new(),
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Helper: DKMS status
+// -----------------------------------------------------------------------------
+
+/// Status of every registered module for `release`; modules without an entry
+/// for that kernel are reported as "missing".
+async fn dkms_modules_for_release(registered: &[DkmsModuleStatus], release: &str) -> Vec<DkmsModuleStatus> {
+    let for_release = dkms_status(Some(release)).await;
+
+    let mut seen: Vec<(String, String)> = Vec::new();
+    let mut result = Vec::new();
+    for module in registered {
+        let key = (module.module.clone(), module.module_version.clone());
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+
+        let status = for_release
+            .iter()
+            .find(|m| m.module == module.module && m.module_version == module.module_version)
+            .map(|m| m.status.clone())
+            .unwrap_or_else(|| "missing".to_string());
+
+        result.push(DkmsModuleStatus {
+            module: module.module.clone(),
+            module_version: module.module_version.clone(),
+            kernel_release: Some(release.to_string()),
+            status,
+        });
+    }
+    result
+}
+
+/// `dkms status [-k release]`; empty when DKMS is not installed.
+async fn dkms_status(release: Option<&str>) -> Vec<DkmsModuleStatus> {
+    let mut args = vec!["status"];
+    if let Some(r) = release {
+        args.extend(["-k", r]);
+    }
+    match run_command("dkms", &args).await {
+        Ok(output) => output.lines().filter_map(parse_dkms_line).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// dkms 3: "nvidia/550.78, 6.9.1-arch1-1, x86_64: installed"
+// dkms 2: "nvidia, 550.78, 6.9.1-arch1-1, x86_64: installed"
+// added only: "nvidia/550.78: added"
+fn parse_dkms_line(line: &str) -> Option<DkmsModuleStatus> {
+    let (fields, status) = line.rsplit_once(": ")?;
+    let status = status.split_whitespace().next().unwrap_or("").to_string();
+    let mut parts: Vec<&str> = fields.split(", ").map(str::trim).collect();
+
+    let (module, module_version) = match parts.first()?.split_once('/') {
+        Some((m, v)) => {
+            let pair = (m.to_string(), v.to_string());
+            parts.remove(0);
+            pair
+        }
+        None if parts.len() >= 2 => {
+            let pair = (parts[0].to_string(), parts[1].to_string());
+            parts.drain(..2);
+            pair
+        }
+        None => return None,
+    };
+
+    Some(DkmsModuleStatus {
+        module,
+        module_version,
+        kernel_release: parts.first().map(|s| s.to_string()),
+        status,
+    })
+}
@@ -0,0 +1,553 @@
+// src/kernel_cmdline.rs
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::bootloader::{
+    detect_bootloader, esp_path, grub_mkconfig_command, set_shell_var, shell_var, BootloaderKind,
+    GRUB_DEFAULTS_PATH,
+};
+use crate::privileged::{list_dir, read_file, run_as_root, write_file, FileChange};
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+/// Used by kernel-install and mkinitcpio for unified kernel images. Only read when
+/// one of those generates the boot entries; GRUB and hand-written entries ignore it.
+const KERNEL_CMDLINE_PATH: &str = "/etc/kernel/cmdline";
+const KERNEL_INSTALL_CONF: &str = "/etc/kernel/install.conf";
+const MKINITCPIO_PRESETS: &str = "/etc/mkinitcpio.d";
+/// grub-mkconfig puts both on the normal entries, GRUB_CMDLINE_LINUX first; only
+/// GRUB_CMDLINE_LINUX goes into recovery entries. New parameters go to the latter key.
+const GRUB_CMDLINE_KEYS: [&str; 2] = ["GRUB_CMDLINE_LINUX", "GRUB_CMDLINE_LINUX_DEFAULT"];
+
+/// Parameters whose values are checked; anything else is accepted with a warning.
+/// An empty value list means the key is a flag and takes no value.
+const KNOWN_PARAMS: &[(&str, &[&str])] = &[
+    ("quiet", &[]),
+    ("splash", &[]),
+    ("nomodeset", &[]),
+    ("rw", &[]),
+    ("ro", &[]),
+    ("single", &[]),
+    ("mitigations", &["off", "auto", "auto,nosmt"]),
+    ("loglevel", &["0", "1", "2", "3", "4", "5", "6", "7"]),
+    ("nvidia-drm.modeset", &["0", "1"]),
+    ("nvidia-drm.fbdev", &["0", "1"]),
+    ("zswap.enabled", &["0", "1"]),
+    ("amd_pstate", &["active", "passive", "guided", "disable"]),
+    ("intel_pstate", &["active", "passive", "disable", "no_hwp", "hwp_only"]),
+    ("intel_iommu", &["on", "off", "sm_on", "sm_off"]),
+    ("amd_iommu", &["on", "off", "force_isolation"]),
+    ("iommu", &["pt", "off", "force", "nopt"]),
+    ("sysrq_always_enabled", &["0", "1"]),
+    ("systemd.unified_cgroup_hierarchy", &["0", "1"]),
+];
+/// Values that take a free-form argument but must have one.
+const VALUE_REQUIRED: &[&str] = &["root", "resume", "rootflags", "rootfstype", "cryptdevice", "init", "console"];
+/// Removing these would leave the system unbootable.
+const PROTECTED_PARAMS: &[&str] = &["root", "cryptdevice", "rd.luks.name", "rd.luks.uuid"];
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct KernelParam {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+impl KernelParam {
+    fn parse(token: &str) -> Self {
+        match token.split_once('=') {
+            Some((k, v)) => KernelParam { key: k.to_string(), value: Some(v.to_string()) },
+            None => KernelParam { key: token.to_string(), value: None },
+        }
+    }
+
+    fn render(&self) -> String {
+        match &self.value {
+            Some(v) => format!("{}={}", self.key, v),
+            None => self.key.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct KernelCmdlineInfo {
+    /// What the running kernel was booted with (`/proc/cmdline`).
+    pub running: Vec<KernelParam>,
+    /// What will be used on the next boot.
+    pub persisted: Vec<KernelParam>,
+    /// "kernel-cmdline", "systemd-boot" or "grub"
+    pub source: String,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KernelCmdlineChange {
+    pub source: String,
+    pub persisted: Vec<KernelParam>,
+    pub warnings: Vec<String>,
+    pub files: Vec<FileChange>,
+    pub commands: Vec<String>,
+    pub applied: bool,
+}
+
+// -----------------------------------------------------------------------------
+// Parsing
+// -----------------------------------------------------------------------------
+
+/// Splits on whitespace, keeping double-quoted values (`acpi_osi="Windows 2015"`) together.
+pub fn parse_cmdline(cmdline: &str) -> Vec<KernelParam> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in cmdline.trim().chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens.iter().map(|t| KernelParam::parse(t)).collect()
+}
+
+fn render_cmdline(params: &[KernelParam]) -> String {
+    params.iter().map(KernelParam::render).collect::<Vec<_>>().join(" ")
+}
+
+// -----------------------------------------------------------------------------
+// Persisted configuration per bootloader
+// -----------------------------------------------------------------------------
+enum CmdlineTarget {
+    KernelCmdline,
+    SystemdBoot(Vec<String>),
+    Grub,
+}
+
+/// Whether boot entries are generated from /etc/kernel/cmdline: unified kernel images
+/// built by mkinitcpio (`*_uki=` in a preset) or entries written by kernel-install.
+fn kernel_cmdline_in_use() -> bool {
+    if !Path::new(KERNEL_CMDLINE_PATH).exists() {
+        return false;
+    }
+    let assigns = |content: &str, key_matches: &dyn Fn(&str) -> bool| {
+        content.lines().any(|line| {
+            let line = line.trim();
+            !line.starts_with('#') && line.split_once('=').is_some_and(|(k, _)| key_matches(k.trim()))
+        })
+    };
+
+    let uki_preset = fs::read_dir(MKINITCPIO_PRESETS)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "preset"))
+                .filter_map(|e| fs::read_to_string(e.path()).ok())
+                .any(|content| assigns(&content, &|k| k.ends_with("_uki")))
+        })
+        .unwrap_or(false);
+    let kernel_install = fs::read_to_string(KERNEL_INSTALL_CONF)
+        .map(|content| assigns(&content, &|k| k == "layout"))
+        .unwrap_or(false);
+    uki_preset || kernel_install
+}
+
+impl CmdlineTarget {
+    fn detect() -> Result<Self, String> {
+        match detect_bootloader() {
+            // GRUB builds its entries from /etc/default/grub only.
+            Some(BootloaderKind::Grub) => Ok(CmdlineTarget::Grub),
+            _ if kernel_cmdline_in_use() => Ok(CmdlineTarget::KernelCmdline),
+            Some(BootloaderKind::SystemdBoot) => {
+                let dir = format!("{}/loader/entries", esp_path().ok_or("ESP not found")?);
+                let mut entries: Vec<String> = list_dir(&dir)?
+                    .into_iter()
+                    .filter(|name| name.ends_with(".conf"))
+                    .map(|name| format!("{}/{}", dir, name))
+                    .collect();
+                entries.sort();
+                Ok(CmdlineTarget::SystemdBoot(entries))
+            }
+            None => Err("No persisted kernel command line found (no /etc/kernel/cmdline, systemd-boot or GRUB).".into()),
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            CmdlineTarget::KernelCmdline => "kernel-cmdline",
+            CmdlineTarget::SystemdBoot(_) => "systemd-boot",
+            CmdlineTarget::Grub => "grub",
+        }
+    }
+
+    fn files(&self) -> Vec<String> {
+        match self {
+            CmdlineTarget::KernelCmdline => vec![KERNEL_CMDLINE_PATH.to_string()],
+            CmdlineTarget::SystemdBoot(entries) => entries.clone(),
+            CmdlineTarget::Grub => vec![GRUB_DEFAULTS_PATH.to_string()],
+        }
+    }
+
+    /// GRUB bakes the command line into grub.cfg and unified kernel images embed
+    /// /etc/kernel/cmdline, so both need regenerating; systemd-boot reads entries at boot.
+    fn regenerate_command(&self) -> Option<Vec<&'static str>> {
+        match self {
+            CmdlineTarget::Grub => Some(grub_mkconfig_command()),
+            CmdlineTarget::KernelCmdline if Path::new("/usr/bin/mkinitcpio").exists() => {
+                Some(vec!["mkinitcpio", "-P"])
+            }
+            _ => None,
+        }
+    }
+
+    /// The command line stored in one file of this target, in the parts it is
+    /// assembled from: one part except for GRUB, which has one per key.
+    fn read_from(&self, content: &str) -> Option<Vec<String>> {
+        match self {
+            CmdlineTarget::KernelCmdline => Some(vec![content
+                .lines()
+                .filter(|l| !l.trim_start().starts_with('#'))
+                .collect::<Vec<_>>()
+                .join(" ")]),
+            // Multiple "options" lines are concatenated by systemd-boot.
+            CmdlineTarget::SystemdBoot(_) => {
+                let options: Vec<&str> = content
+                    .lines()
+                    .filter_map(|l| l.trim().strip_prefix("options"))
+                    .map(str::trim)
+                    .collect();
+                (!options.is_empty()).then(|| vec![options.join(" ")])
+            }
+            CmdlineTarget::Grub => {
+                Some(GRUB_CMDLINE_KEYS.iter().map(|key| shell_var(content, key).unwrap_or_default()).collect())
+            }
+        }
+    }
+
+    fn write_to(&self, content: &str, parts: &[String]) -> String {
+        let cmdline = parts.join(" ");
+        match self {
+            CmdlineTarget::KernelCmdline => format!("{}\n", cmdline),
+            CmdlineTarget::SystemdBoot(_) => {
+                let mut written = false;
+                content
+                    .lines()
+                    .filter_map(|l| {
+                        if !l.trim().starts_with("options") {
+                            return Some(l.to_string());
+                        }
+                        // Collapse all options lines into the first one.
+                        (!std::mem::replace(&mut written, true)).then(|| format!("options {}", cmdline))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+                    + "\n"
+            }
+            // Keys that did not change are left alone, so a missing one is not added.
+            CmdlineTarget::Grub => {
+                let mut content = content.to_string();
+                for (key, part) in GRUB_CMDLINE_KEYS.iter().zip(parts) {
+                    if shell_var(&content, key).unwrap_or_default() != *part {
+                        content = set_shell_var(&content, key, part);
+                    }
+                }
+                content
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Validation
+// -----------------------------------------------------------------------------
+fn validate_param(param: &KernelParam, warnings: &mut Vec<String>) -> Result<(), String> {
+    let key_ok = !param.key.is_empty()
+        && param.key.chars().all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c));
+    if !key_ok {
+        return Err(format!("Invalid parameter name '{}'", param.key));
+    }
+    if let Some(v) = &param.value {
+        // grub-mkconfig sources /etc/default/grub as root, where these would expand.
+        if v.contains(['$', '`', '\\', '\n']) {
+            return Err(format!("Invalid value for '{}': '$', '`', '\\' and line breaks are not allowed.", param.key));
+        }
+        if v.is_empty() || v.contains(char::is_whitespace) && !(v.starts_with('"') && v.ends_with('"')) {
+            return Err(format!("Invalid value for '{}': '{}'", param.key, v));
+        }
+    }
+
+    if let Some((_, allowed)) = KNOWN_PARAMS.iter().find(|(k, _)| *k == param.key) {
+        match (&param.value, allowed.is_empty()) {
+            (Some(_), true) => return Err(format!("'{}' does not take a value", param.key)),
+            (None, false) => return Err(format!("'{}' needs one of: {}", param.key, allowed.join(", "))),
+            (Some(v), false) if !allowed.contains(&v.as_str()) => {
+                return Err(format!("Invalid value '{}' for '{}', expected one of: {}", v, param.key, allowed.join(", ")))
+            }
+            _ => {}
+        }
+    } else if VALUE_REQUIRED.contains(&param.key.as_str()) {
+        if param.value.is_none() {
+            return Err(format!("'{}' needs a value", param.key));
+        }
+    } else {
+        warnings.push(format!("'{}' is not a known parameter, check the spelling.", param.key));
+    }
+    Ok(())
+}
+
+/// Applies removals, then additions, to the parts of a command line. A key is removed
+/// from every part holding it; adding an existing key replaces its value in place,
+/// and new keys are appended to the last part.
+fn edit_params(
+    mut parts: Vec<Vec<KernelParam>>,
+    add: &[String],
+    remove: &[String],
+) -> Result<(Vec<Vec<KernelParam>>, Vec<String>), String> {
+    let mut warnings = Vec::new();
+
+    for key in remove {
+        if PROTECTED_PARAMS.contains(&key.as_str()) {
+            return Err(format!("Refusing to remove '{}', the system would not boot.", key));
+        }
+        for params in &mut parts {
+            params.retain(|p| &p.key != key);
+        }
+    }
+
+    for token in add {
+        let param = KernelParam::parse(token.trim());
+        validate_param(&param, &mut warnings)?;
+        match parts.iter_mut().flatten().find(|p| p.key == param.key) {
+            Some(existing) => *existing = param,
+            None => {
+                if let Some(last) = parts.last_mut() {
+                    last.push(param);
+                }
+            }
+        }
+    }
+
+    Ok((parts, warnings))
+}
+
+fn plan_change(add: &[String], remove: &[String]) -> Result<KernelCmdlineChange, String> {
+    let target = CmdlineTarget::detect()?;
+    let mut files = Vec::new();
+    let mut persisted = Vec::new();
+    let mut warnings = Vec::new();
+
+    for path in target.files() {
+        let original = read_file(&path)?;
+        let Some(parts) = target.read_from(&original) else { continue };
+
+        let (parts, file_warnings) = edit_params(parts.iter().map(|p| parse_cmdline(p)).collect(), add, remove)?;
+        if persisted.is_empty() {
+            persisted = parts.concat();
+            warnings = file_warnings;
+        }
+
+        let rendered: Vec<String> = parts.iter().map(|p| render_cmdline(p)).collect();
+        let change = FileChange::new(&path, original.clone(), target.write_to(&original, &rendered));
+        if !change.is_empty() {
+            files.push(change);
+        }
+    }
+
+    let commands = match target.regenerate_command() {
+        Some(cmd) if !files.is_empty() => vec![cmd.join(" ")],
+        _ => Vec::new(),
+    };
+
+    Ok(KernelCmdlineChange {
+        source: target.source().to_string(),
+        persisted,
+        warnings,
+        files,
+        commands,
+        applied: false,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Tauri commands
+// -----------------------------------------------------------------------------
+#[tauri::command]
+pub async fn get_kernel_cmdline() -> Result<KernelCmdlineInfo, String> {
+    // read_file falls back to pkexec for entries on a root-only ESP.
+    tokio::task::spawn_blocking(read_kernel_cmdline)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn read_kernel_cmdline() -> Result<KernelCmdlineInfo, String> {
+    let running = fs::read_to_string("/proc/cmdline")
+        .map(|c| parse_cmdline(&c))
+        .map_err(|e| format!("read /proc/cmdline: {}", e))?;
+
+    let target = CmdlineTarget::detect()?;
+    let files = target.files();
+    let persisted = files
+        .iter()
+        .find_map(|path| read_file(path).ok().and_then(|c| target.read_from(&c)))
+        .map(|parts| parse_cmdline(&parts.join(" ")))
+        .unwrap_or_default();
+
+    Ok(KernelCmdlineInfo {
+        running,
+        persisted,
+        source: target.source().to_string(),
+        files,
+    })
+}
+
+/// `add` takes "key" or "key=value" tokens, `remove` takes keys.
+#[tauri::command]
+pub async fn preview_kernel_cmdline_change(
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<KernelCmdlineChange, String> {
+    tokio::task::spawn_blocking(move || plan_change(&add, &remove))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn apply_kernel_cmdline_change(
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<KernelCmdlineChange, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut plan = plan_change(&add, &remove)?;
+
+        for file in &plan.files {
+            write_file(&file.path, &file.updated)?;
+        }
+        if !plan.commands.is_empty() {
+            if let Some(cmd) = CmdlineTarget::detect()?.regenerate_command() {
+                run_as_root(cmd[0], &cmd[1..])?;
+            }
+        }
+
+        plan.applied = true;
+        Ok(plan)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(params: &[KernelParam]) -> Vec<String> {
+        params.iter().map(KernelParam::render).collect()
+    }
+
+    #[test]
+    fn cmdline_parsing_keeps_quoted_values_together() {
+        let cases: [(&str, &[&str]); 4] = [
+            ("root=UUID=1234 rw quiet", &["root=UUID=1234", "rw", "quiet"]),
+            ("  quiet   splash\n", &["quiet", "splash"]),
+            ("acpi_osi=\"Windows 2015\" quiet", &["acpi_osi=\"Windows 2015\"", "quiet"]),
+            ("", &[]),
+        ];
+        for (cmdline, expected) in cases {
+            assert_eq!(keys(&parse_cmdline(cmdline)), expected, "{:?}", cmdline);
+        }
+        assert_eq!(parse_cmdline("rd.luks.name=abc=root")[0].value.as_deref(), Some("abc=root"));
+    }
+
+    #[test]
+    fn targets_read_their_cmdline_parts() {
+        let cases: [(CmdlineTarget, &str, Option<&[&str]>); 7] = [
+            (CmdlineTarget::KernelCmdline, "# comment\nroot=/dev/sda2 rw\nquiet\n", Some(&["root=/dev/sda2 rw quiet"])),
+            (CmdlineTarget::SystemdBoot(Vec::new()), "title Arch\noptions root=/dev/sda2\noptions quiet\n", Some(&["root=/dev/sda2 quiet"])),
+            (CmdlineTarget::SystemdBoot(Vec::new()), "title Arch\nlinux /vmlinuz-linux\n", None),
+            (
+                CmdlineTarget::Grub,
+                "GRUB_CMDLINE_LINUX=\"cryptdevice=UUID=1:root\"\nGRUB_CMDLINE_LINUX_DEFAULT=\"loglevel=3 quiet\"\n",
+                Some(&["cryptdevice=UUID=1:root", "loglevel=3 quiet"]),
+            ),
+            (CmdlineTarget::Grub, "GRUB_CMDLINE_LINUX_DEFAULT='quiet'\n", Some(&["", "quiet"])),
+            (CmdlineTarget::Grub, "#GRUB_CMDLINE_LINUX=\"nomodeset\"\nGRUB_CMDLINE_LINUX=\"\"\n", Some(&["", ""])),
+            (CmdlineTarget::Grub, "", Some(&["", ""])),
+        ];
+        for (target, content, expected) in cases {
+            let expected = expected.map(|parts| parts.iter().map(|p| p.to_string()).collect::<Vec<_>>());
+            assert_eq!(target.read_from(content), expected, "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn grub_edits_touch_the_key_holding_the_parameter() {
+        let target = CmdlineTarget::Grub;
+        let content = "GRUB_TIMEOUT=5\nGRUB_CMDLINE_LINUX=\"nomodeset\"\nGRUB_CMDLINE_LINUX_DEFAULT=\"loglevel=3 quiet\"\n";
+        let edit = |content: &str, add: &[&str], remove: &[&str]| {
+            let parts = target.read_from(content).unwrap().iter().map(|p| parse_cmdline(p)).collect();
+            let add: Vec<String> = add.iter().map(|s| s.to_string()).collect();
+            let remove: Vec<String> = remove.iter().map(|s| s.to_string()).collect();
+            let (parts, _) = edit_params(parts, &add, &remove).unwrap();
+            let rendered: Vec<String> = parts.iter().map(|p| render_cmdline(p)).collect();
+            target.write_to(content, &rendered)
+        };
+
+        let cases: [(&[&str], &[&str], &str); 4] = [
+            (&[], &["nomodeset"], "GRUB_TIMEOUT=5\nGRUB_CMDLINE_LINUX=\"\"\nGRUB_CMDLINE_LINUX_DEFAULT=\"loglevel=3 quiet\"\n"),
+            (&["splash"], &["quiet"], "GRUB_TIMEOUT=5\nGRUB_CMDLINE_LINUX=\"nomodeset\"\nGRUB_CMDLINE_LINUX_DEFAULT=\"loglevel=3 splash\"\n"),
+            (&["loglevel=4"], &[], "GRUB_TIMEOUT=5\nGRUB_CMDLINE_LINUX=\"nomodeset\"\nGRUB_CMDLINE_LINUX_DEFAULT=\"loglevel=4 quiet\"\n"),
+            (&[], &["missing"], content),
+        ];
+        for (add, remove, expected) in cases {
+            assert_eq!(edit(content, add, remove), expected, "add {:?} remove {:?}", add, remove);
+        }
+
+        // A missing key is only written when a parameter lands in it.
+        assert_eq!(edit("GRUB_TIMEOUT=5\n", &["quiet"], &[]), "GRUB_TIMEOUT=5\nGRUB_CMDLINE_LINUX_DEFAULT=\"quiet\"\n");
+    }
+
+    #[test]
+    fn systemd_boot_options_lines_are_collapsed() {
+        let target = CmdlineTarget::SystemdBoot(Vec::new());
+        let content = "title Arch\noptions root=/dev/sda2\nlinux /vmlinuz-linux\noptions quiet\n";
+        assert_eq!(
+            target.write_to(content, &["root=/dev/sda2 splash".to_string()]),
+            "title Arch\noptions root=/dev/sda2 splash\nlinux /vmlinuz-linux\n"
+        );
+    }
+
+    #[test]
+    fn edits_are_validated() {
+        let edit = |add: &[&str], remove: &[&str]| {
+            let add: Vec<String> = add.iter().map(|s| s.to_string()).collect();
+            let remove: Vec<String> = remove.iter().map(|s| s.to_string()).collect();
+            edit_params(vec![parse_cmdline("root=/dev/sda2 quiet")], &add, &remove)
+        };
+        assert!(edit(&[], &["root"]).is_err());
+        assert!(edit(&["quiet=1"], &[]).is_err());
+        assert!(edit(&["mitigations=maybe"], &[]).is_err());
+        assert!(edit(&["resume"], &[]).is_err());
+        assert!(edit(&["bad key"], &[]).is_err());
+        for value in ["foo=$(id)", "foo=`id`", "foo=a\\b", "foo=\"a\nb\"", "foo=${HOME}"] {
+            assert!(edit(&[value], &[]).is_err(), "{:?}", value);
+        }
+        let (_, warnings) = edit(&["my_driver.option=1"], &[]).unwrap();
+        assert_eq!(warnings.len(), 1);
+        let (parts, warnings) = edit(&["mitigations=off"], &["quiet"]).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(keys(&parts[0]), ["root=/dev/sda2", "mitigations=off"]);
+    }
+}
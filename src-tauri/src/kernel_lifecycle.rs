@@ -0,0 +1,277 @@
+// src/kernel_lifecycle.rs
+use chrono::{Local, NaiveDate};
+use serde::Deserialize;
+use std::fs;
+use tauri::{AppHandle, Manager};
+
+use crate::model::{InstallableKernel, KernelInfo};
+
+/// Shipped with the app; a copy in the app data dir with the same name takes precedence,
+/// so the table can be refreshed without a new release.
+const BUNDLED_LIFECYCLE_DATA: &str = include_str!("../data/kernel-lifecycle.json");
+const LIFECYCLE_DATA_FILE: &str = "kernel-lifecycle.json";
+
+// -----------------------------------------------------------------------------
+// Data file
+// -----------------------------------------------------------------------------
+#[derive(Debug, Deserialize)]
+pub struct LifecycleData {
+    pub updated: String,
+    pub series: Vec<SeriesInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeriesInfo {
+    pub series: String,
+    pub lts: bool,
+    pub eol: Option<NaiveDate>,
+}
+
+pub fn load_lifecycle_data(app_handle: &AppHandle) -> LifecycleData {
+    let override_data = app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join(LIFECYCLE_DATA_FILE)).ok())
+        .and_then(|raw| match serde_json::from_str(&raw) {
+            Ok(data) => Some(data),
+            Err(e) => {
+                eprintln!("Ignoring invalid {}: {}", LIFECYCLE_DATA_FILE, e);
+                None
+            }
+        });
+
+    override_data.unwrap_or_else(|| {
+        serde_json::from_str(BUNDLED_LIFECYCLE_DATA).expect("bundled kernel-lifecycle.json is valid")
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Classification
+// -----------------------------------------------------------------------------
+
+/// "6.12.30-1" / "6.9.1.arch1-1" / "6.10rc3-1" → (6, 12) / (6, 9) / (6, 10)
+pub fn kernel_series(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split(['.', '-']);
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?;
+    let digits = minor.find(|c: char| !c.is_ascii_digit()).unwrap_or(minor.len());
+    let minor = minor[..digits].parse().ok()?;
+    Some((major, minor))
+}
+
+/// "6.10rc3-1", "6.10.0-rc3-1-mainline" (but not "6.9.1.arch1-1")
+fn is_release_candidate(version: &str) -> bool {
+    version.match_indices("rc").any(|(i, _)| {
+        let before = version[..i].chars().last();
+        let after = version[i + 2..].chars().next();
+        matches!(before, Some(c) if c.is_ascii_digit() || c == '-' || c == '.')
+            && after.is_some_and(|c| c.is_ascii_digit())
+    })
+}
+
+fn series_string((major, minor): (u32, u32)) -> String {
+    format!("{}.{}", major, minor)
+}
+
+/// "mainline", "stable", "lts", "eol" or "unknown", plus the EOL date when known.
+///
+/// A non-LTS series is supported until the next series is released, so anything
+/// older than `newest_stable` that is not LTS is end of life.
+fn classify(
+    data: &LifecycleData,
+    package_name: &str,
+    version: &str,
+    newest_stable: Option<(u32, u32)>,
+    today: NaiveDate,
+) -> (Option<String>, String, Option<NaiveDate>) {
+    let Some(series) = kernel_series(version) else {
+        return (None, "unknown".to_string(), None);
+    };
+    let series_str = series_string(series);
+
+    if is_release_candidate(version) || package_name.contains("mainline") || package_name.ends_with("-git") {
+        return (Some(series_str), "mainline".to_string(), None);
+    }
+
+    if let Some(info) = data.series.iter().find(|s| s.series == series_str) {
+        let lifecycle = match info.eol {
+            Some(eol) if eol < today => "eol",
+            _ if info.lts && tracks_lts(package_name) => "lts",
+            _ => "stable",
+        };
+        return (Some(series_str), lifecycle.to_string(), info.eol);
+    }
+
+    let lifecycle = match newest_stable {
+        Some(newest) if series < newest => "eol",
+        _ => "stable",
+    };
+    (Some(series_str), lifecycle.to_string(), None)
+}
+
+/// Whether a package stays on its series. `linux` and `linux-zen` move on to the
+/// next series, so a series later becoming LTS does not make them LTS kernels;
+/// `linux-lts`, `linux-rt-lts` and Manjaro's series packages (`linux612`) stay.
+fn tracks_lts(package_name: &str) -> bool {
+    let series_named = package_name
+        .strip_prefix("linux")
+        .is_some_and(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()));
+    package_name.split('-').any(|part| part.starts_with("lts")) || series_named
+}
+
+/// Fills lifecycle fields on every installable kernel, flags an EOL running kernel
+/// and recommends the newest LTS package available in the repositories.
+pub fn apply_lifecycle(info: &mut KernelInfo, data: &LifecycleData) {
+    let today = Local::now().date_naive();
+
+    let newest_stable = info
+        .installable_kernels
+        .iter()
+        .filter(|k| !is_release_candidate(&k.version))
+        .filter_map(|k| kernel_series(&k.version))
+        .max();
+
+    for kernel in &mut info.installable_kernels {
+        let (series, lifecycle, eol) = classify(data, &kernel.package_name, &kernel.version, newest_stable, today);
+        kernel.series = series;
+        kernel.lifecycle = lifecycle;
+        kernel.eol_date = eol.map(|d| d.to_string());
+    }
+
+    let running_package = info
+        .installed_kernels
+        .iter()
+        .find(|k| k.running)
+        .map(|k| k.name.clone())
+        .unwrap_or_default();
+    let (_, running_lifecycle, running_eol) =
+        classify(data, &running_package, &info.running_kernel, newest_stable, today);
+    info.running_kernel_eol = running_lifecycle == "eol";
+    info.running_kernel_lifecycle = running_lifecycle;
+    info.running_kernel_eol_date = running_eol.map(|d| d.to_string());
+
+    let recommended = newest_lts(&info.installable_kernels).map(|k| k.package_name.clone());
+    for kernel in &mut info.installable_kernels {
+        kernel.recommended = recommended.as_deref() == Some(kernel.package_name.as_str());
+    }
+    info.recommended_kernel = recommended;
+    info.lifecycle_data_updated = data.updated.clone();
+}
+
+// Prefer the plain LTS kernel over variants (rt-lts, ...) of the same series.
+fn newest_lts(kernels: &[InstallableKernel]) -> Option<&InstallableKernel> {
+    kernels
+        .iter()
+        .filter(|k| k.lifecycle == "lts")
+        .max_by_key(|k| (kernel_series(&k.version), k.flavor == "lts" || k.flavor == "default"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> LifecycleData {
+        serde_json::from_str(
+            r#"{ "updated": "2025-12-01", "series": [
+                { "series": "6.12", "lts": true, "eol": "2026-12-31" },
+                { "series": "6.6", "lts": true, "eol": "2026-12-31" },
+                { "series": "5.4", "lts": true, "eol": "2025-12-31" }
+            ] }"#,
+        )
+        .unwrap()
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()
+    }
+
+    fn kernel(package_name: &str, version: &str, flavor: &str) -> InstallableKernel {
+        InstallableKernel {
+            package_name: package_name.to_string(),
+            version: version.to_string(),
+            description: String::new(),
+            flavor: flavor.to_string(),
+            repository: "core".to_string(),
+            series: None,
+            lifecycle: String::new(),
+            eol_date: None,
+            recommended: false,
+        }
+    }
+
+    #[test]
+    fn bundled_data_parses() {
+        let data: LifecycleData = serde_json::from_str(BUNDLED_LIFECYCLE_DATA).unwrap();
+        assert!(!data.series.is_empty());
+        assert!(data.series.iter().all(|s| kernel_series(&s.series).is_some()), "{:?}", data.series);
+    }
+
+    #[test]
+    fn series_and_release_candidates() {
+        let cases = [
+            ("6.12.30-1", Some((6, 12)), false),
+            ("6.9.1.arch1-1", Some((6, 9)), false),
+            ("6.6.30-1-lts", Some((6, 6)), false),
+            ("6.10rc3-1", Some((6, 10)), true),
+            ("6.10.0-rc3-1-mainline", Some((6, 10)), true),
+            ("6.12.0.rc1-1", Some((6, 12)), true),
+            ("git-1", None, false),
+        ];
+        for (version, series, rc) in cases {
+            assert_eq!(kernel_series(version), series, "{}", version);
+            assert_eq!(is_release_candidate(version), rc, "{}", version);
+        }
+    }
+
+    #[test]
+    fn lts_is_a_property_of_the_package() {
+        for name in ["linux-lts", "linux-rt-lts", "linux-lts612", "linux612"] {
+            assert!(tracks_lts(name), "{}", name);
+        }
+        for name in ["linux", "linux-zen", "linux-hardened", "linux-rt", "linux-alts"] {
+            assert!(!tracks_lts(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn classification() {
+        let data = data();
+        let newest = Some((6, 13));
+        let classify = |name: &str, version: &str| {
+            let (series, lifecycle, eol) = classify(&data, name, version, newest, today());
+            (series, lifecycle, eol.map(|d| d.to_string()))
+        };
+        let s = |v: &str| Some(v.to_string());
+
+        assert_eq!(classify("linux-lts", "6.12.30-1"), (s("6.12"), s("lts").unwrap(), s("2026-12-31")));
+        // `linux` moves on to the next series, so it is never an LTS kernel.
+        assert_eq!(classify("linux", "6.12.30.arch1-1"), (s("6.12"), s("stable").unwrap(), s("2026-12-31")));
+        assert_eq!(classify("linux", "6.13.2.arch1-1"), (s("6.13"), s("stable").unwrap(), None));
+        // Not in the table and older than the newest release: replaced, so EOL.
+        assert_eq!(classify("linux-zen", "6.11.9.zen1-1"), (s("6.11"), s("eol").unwrap(), None));
+        assert_eq!(classify("linux-lts54", "5.4.280-1"), (s("5.4"), s("eol").unwrap(), s("2025-12-31")));
+        assert_eq!(classify("linux-mainline", "6.14rc2-1"), (s("6.14"), s("mainline").unwrap(), None));
+        assert_eq!(classify("linux-git", "6.13.r1234.g0abc-1"), (s("6.13"), s("mainline").unwrap(), None));
+        assert_eq!(classify("linux", "unknown"), (None, s("unknown").unwrap(), None));
+    }
+
+    #[test]
+    fn newest_lts_prefers_the_plain_package() {
+        let data = data();
+        let today = today();
+        let mut kernels = vec![
+            kernel("linux", "6.13.2.arch1-1", "default"),
+            kernel("linux-rt-lts", "6.12.20.1-1", "rt"),
+            kernel("linux-lts", "6.12.30-1", "lts"),
+            kernel("linux-lts66", "6.6.90-1", "lts"),
+        ];
+        for k in &mut kernels {
+            k.lifecycle = classify(&data, &k.package_name, &k.version, Some((6, 13)), today).1;
+        }
+        assert_eq!(newest_lts(&kernels).map(|k| k.package_name.as_str()), Some("linux-lts"));
+
+        kernels.retain(|k| k.package_name != "linux-lts");
+        assert_eq!(newest_lts(&kernels).map(|k| k.package_name.as_str()), Some("linux-rt-lts"));
+    }
+}
@@ -8,13 +8,36 @@ use serde::{Serialize};
 use sysinfo::{System}; // Only System is needed here after refactoring
 
 mod pacman_manager;
+mod pacman_db;
+mod package_graph;
+mod package_footprint;
+mod snapshot;
+mod kernel;
+mod kernel_lifecycle;
+mod privileged;
+mod bootloader;
+mod kernel_cmdline;
+mod initramfs;
+mod restart_scan;
 mod hardware;
+mod hwmon;
 mod model; // NEW: Import the model module
+mod alerts;
+mod metrics_history;
+mod smart;
+mod storage;
+mod udisks;
+mod network_link;
+mod power_profile;
+mod power_supply;
+mod process_manager;
 mod system; // NEW: Import the system module
 
 use hardware::get_hardware_info;
-use model::*; // Import all structs from model.rs
-use system::start_system_monitor; // Import the new command
+use system::{
+    get_system_monitor_status, pause_system_monitor, resume_system_monitor, set_system_monitor_topics,
+    start_system_monitor, stop_system_monitor, SystemMonitor,
+};
 
 mod bluetooth;
 mod printers;
@@ -59,154 +82,6 @@ pub struct UserInfo {
 
 
 
-// --- The Core Tauri Command Function ---
-
-#[tauri::command]
-async fn get_system_kernels() -> Result<String, String> {
-    let running_kernel = get_running_kernel_version();
-    let installed_kernels = get_installed_kernels().map_err(|e| format!("Failed to get installed kernels: {}", e))?;
-    let installable_kernels = get_installable_kernels_from_repos().await.map_err(|e| format!("Failed to get installable kernels: {}", e))?;
-
-    let result = KernelInfo {
-        running_kernel,
-        installed_kernels,
-        installable_kernels,
-    };
-
-    // Serialize the final struct to a JSON string
-    serde_json::to_string_pretty(&result)
-        .map_err(|e| format!("Failed to serialize kernel info to JSON: {}", e))
-}
-
-fn get_running_kernel_version() -> String {
-    let mut sys = System::new();
-    // This call is now redundant if kernel_version() is an associated function
-    sys.refresh_all(); 
-
-    
-    sysinfo::System::kernel_version()
-        .unwrap_or_else(|| "<unknown>".to_owned())
-}
-async fn run_command(cmd: &str, args: &[&str]) -> Result<String, String> {
-    let output = tokio::process::Command::new(cmd)
-        .args(args)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to execute command '{}': {}", cmd, e))?;
-
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-    } else {
-        Err(format!(
-            "Command failed: {} {}", 
-            cmd, 
-            String::from_utf8_lossy(&output.stderr)
-        ))
-    }
-}
-
-fn get_installed_kernels() -> Result<Vec<InstalledKernel>, std::io::Error> {
-    // NOTE: For a real-world Tauri app, you might want to use 
-    // `tokio::process::Command` here as well, but for simplicity
-    // and correctness, we'll shell out to 'pacman' later on.
-    
-    // Simplified logic: Read files in /lib/modules/
-    let mut installed_kernels = Vec::new();
-    let modules_path = "/lib/modules";
-
-    for entry in std::fs::read_dir(modules_path)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_dir() {
-            if let Some(dir_name) = path.file_name().and_then(|s| s.to_str()) {
-                // A very simplified attempt to determine flavor based on common suffixes
-                let flavor = if dir_name.contains("lts") {
-                    "lts".to_string()
-                } else if dir_name.contains("rt") {
-                    "rt".to_string()
-                } else if dir_name.contains("zen") {
-                    "zen".to_string()
-                } else {
-                    "default".to_string() // Fallback for the main or other kernels
-                };
-
-                installed_kernels.push(InstalledKernel {
-                    name: format!("linux-{}", flavor), // Guess a package name
-                    version: dir_name.to_string(),
-                    flavor,
-                });
-            }
-        }
-    }
-
-    Ok(installed_kernels)
-}
-// Revised function to make parsing more defensive
-async fn get_installable_kernels_from_repos() -> Result<Vec<InstallableKernel>, String> {
-    // Search for all packages starting with 'linux-' (kernels, headers, etc.)
-    // We use the regex anchor '^' to only get packages that start with the prefix.
-    let output = run_command("pacman", &["-Ss", "^linux-"]).await?;
-
-    let mut installable_list = Vec::new();
-    let mut current_pkg_name: Option<String> = None;
-
-    for line in output.lines() {
-        // Line 1: 'repo/package-name version'
-        // Example: 'core/linux 6.6.1-arch1-1'
-        if line.starts_with("core/") || line.starts_with("extra/") || line.starts_with("community/") {
-            // New package entry starts
-            
-            // 1. Extract package name and version
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 2 { continue; } // Skip malformed lines
-
-            // parts[0] is typically 'repo/package-name'
-            let full_name = parts[0];
-            let pkg_name = full_name.split('/').nth(1).unwrap_or("unknown-pkg").to_string();
-            let pkg_version = parts[1].to_string();
-
-            // Skip packages that are clearly headers or docs, we only want the kernel image package
-            if pkg_name.ends_with("-headers") || pkg_name.ends_with("-docs") {
-                current_pkg_name = None;
-                continue;
-            }
-
-            // 2. Determine flavor
-            let flavor = if pkg_name.contains("lts") {
-                "lts"
-            } else if pkg_name.contains("rt") {
-                "rt"
-            } else if pkg_name.contains("zen") {
-                "zen"
-            } else if pkg_name == "linux" {
-                "main" // Standard mainline kernel
-            } else {
-                "other"
-            };
-
-            // 3. Create a new entry and store the name to look for the description next
-            current_pkg_name = Some(pkg_name.clone());
-            installable_list.push(InstallableKernel {
-                package_name: pkg_name,
-                version: pkg_version,
-                description: String::new(), // Will be filled in the next step
-                flavor: flavor.to_string(),
-            });
-
-        // Line 2: '    Description: ...'
-        } else if line.trim().starts_with("Description:") {
-            if let Some(name) = current_pkg_name.take() {
-                // Find the last entry we just created
-                if let Some(entry) = installable_list.iter_mut().rev().find(|e| e.package_name == name) {
-                    let desc = line.split(':').nth(1).unwrap_or("").trim().to_string();
-                    entry.description = desc;
-                }
-            }
-        }
-    }
-
-    Ok(installable_list)
-}
 #[tauri::command]
 fn get_user_profile_photo_base64() -> Result<String, String> {
     // 1. Get the user's home directory
@@ -300,6 +175,14 @@ unsafe { // Not unsafe if you don't use edition 2024
 }
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
+        .manage(SystemMonitor::default())
+        .manage(process_manager::ProcessManager::default())
+        .manage(smart::DriveHealthTracker::default())
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                window.state::<SystemMonitor>().release_window(window.label());
+            }
+        })
         .setup(|app| {
             #[cfg(debug_assertions)] // only include this code on debug builds
             {
@@ -307,6 +190,9 @@ unsafe { // Not unsafe if you don't use edition 2024
                 window.open_devtools();
                 window.close_devtools();
             }
+            alerts::start(app.handle());
+            let storage_handle = app.handle().clone();
+            tauri::async_runtime::spawn(udisks::watch_storage(storage_handle));
             let handle = app.handle().clone();
             // Optional: emit current list on startup
             tauri::async_runtime::spawn(async move {
@@ -324,15 +210,48 @@ unsafe { // Not unsafe if you don't use edition 2024
             get_locale_status,
             set_system_locale,
             generate_locale,
+            reboot::get_reboot_reasons,
 printers::get_printers, 
             printers::add_printer_cmd, 
             printers::remove_printer_cmd,
            start_system_monitor,
+            stop_system_monitor,
+            set_system_monitor_topics,
+            pause_system_monitor,
+            resume_system_monitor,
+            get_system_monitor_status,
+            metrics_history::get_metrics_history,
+            alerts::get_alert_rules,
+            alerts::save_alert_rules,
+            alerts::reset_alert_rules,
+            alerts::get_active_alerts,
+            process_manager::list_processes,
+            storage::get_block_devices,
+            smart::get_drive_health,
+            power_profile::get_power_profile,
+            power_profile::set_power_profile,
+            power_profile::set_cpu_governor,
+            udisks::mount_filesystem,
+            udisks::unmount_filesystem,
+            udisks::unlock_luks_volume,
+            udisks::lock_luks_volume,
+            udisks::eject_drive,
+            udisks::power_off_drive,
+            process_manager::send_process_signal,
+            process_manager::renice_process,
+            process_manager::set_process_io_priority,
             hardware_info,
             pacman_manager::manage_pacman_package,
             pacman_manager::check_package_status,
             pacman_manager::check_system_updates,
              pacman_manager::check_packages_status,
+            package_graph::get_dependency_tree,
+            package_footprint::get_disk_footprint,
+            snapshot::get_snapshot_tool,
+            snapshot::list_snapshots,
+            snapshot::delete_snapshot,
+            snapshot::compare_snapshot_packages,
+            snapshot::schedule_snapshot_restore,
 
        bluetooth::start_discovery,
             bluetooth::connect_device,
@@ -340,7 +259,21 @@ printers::get_printers,
             bluetooth::pair_device,
             bluetooth::remove_device,
             bluetooth::list_paired_devices,
-            get_system_kernels,
+            kernel::get_system_kernels,
+            kernel::install_kernel,
+            kernel::remove_kernel,
+            bootloader::get_bootloader_info,
+            bootloader::preview_bootloader_changes,
+            bootloader::apply_bootloader_changes,
+            kernel_cmdline::get_kernel_cmdline,
+            kernel_cmdline::preview_kernel_cmdline_change,
+            kernel_cmdline::apply_kernel_cmdline_change,
+            initramfs::get_initramfs_config,
+            initramfs::preview_initramfs_change,
+            initramfs::apply_initramfs_change,
+            initramfs::regenerate_initramfs,
+            restart_scan::scan_processes_needing_restart,
+            restart_scan::restart_services,
             get_distro])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
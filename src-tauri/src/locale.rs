@@ -1,6 +1,6 @@
 // src/locale.rs
 use crate::model::{LocaleInfo, LocaleStatus};
-use crate::reboot::is_reboot_required;
+use crate::reboot::reboot_reasons;
 use serde_json::{json, Value};
 use std::fs;
 use std::process::{Command, Output};
@@ -94,13 +94,14 @@ pub async fn get_locale_status(app_handle: AppHandle) -> Result<Value, String> {
     let current = parse_locale_conf()?;
     let available = available_locales()?;
     let generated = generated_locales()?;
-    let reboot = is_reboot_required();
+    let reasons = reboot_reasons();
 
     let status = LocaleStatus {
         current,
         available_locales: available,
         generated_locales: generated,
-        reboot_required: reboot,
+        reboot_required: !reasons.is_empty(),
+        reboot_reasons: reasons,
     };
 
     emit_status(app_handle.clone(), status.clone());
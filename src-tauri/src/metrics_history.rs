@@ -0,0 +1,340 @@
+// src/metrics_history.rs
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State, Wry};
+
+use crate::model::SystemData;
+use crate::system::SystemMonitor;
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+const FINE_STEP_MS: i64 = 1_000;
+const FINE_RETENTION_MS: i64 = 10 * 60 * 1_000;
+const COARSE_STEP_MS: i64 = 60 * 1_000;
+const COARSE_RETENTION_MS: i64 = 7 * 24 * 60 * 60 * 1_000;
+/// The fine tier is rewritten whole this often so a restart loses at most this much.
+const FINE_FLUSH_MS: i64 = 60 * 1_000;
+const FINE_FILE: &str = "metrics-1s.json";
+/// One JSON point per line, appended every minute and compacted on load.
+const COARSE_FILE: &str = "metrics-1m.jsonl";
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MetricPoint {
+    /// Unix time in milliseconds; for 1 min points, the start of the minute.
+    pub t: i64,
+    pub cpu_percent: f32,
+    pub memory_used_percent: f32,
+    pub swap_used_percent: f32,
+    /// Summed over all interfaces.
+    pub net_rx_bytes_per_s: f64,
+    pub net_tx_bytes_per_s: f64,
+    /// Used space per mount point.
+    pub disk_used_percent: BTreeMap<String, f32>,
+    /// Per hwmon sensor ID (labels repeat across chips and are not stable).
+    pub temperatures_c: BTreeMap<String, f32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricsSeries {
+    /// 1 or 60.
+    pub resolution_s: u32,
+    pub from: i64,
+    pub to: i64,
+    pub points: Vec<MetricPoint>,
+}
+
+/// Two tiers fed by the live monitor: 1 s points for the last 10 minutes and
+/// 1 min averages for the last 7 days, both mirrored to the app data dir.
+/// Nothing is recorded while the monitor is stopped.
+#[derive(Default)]
+pub struct MetricsHistory {
+    dir: Option<PathBuf>,
+    loaded: bool,
+    fine: VecDeque<MetricPoint>,
+    coarse: VecDeque<MetricPoint>,
+    /// Fine points of the minute being aggregated.
+    current_minute: Vec<MetricPoint>,
+    /// (time, total rx, total tx) of the previous point, for throughput.
+    previous_totals: Option<(i64, u64, u64)>,
+    last_fine_flush: i64,
+}
+
+pub fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn percent(used: f64, total: f64) -> f32 {
+    if total > 0.0 { (used / total * 100.0) as f32 } else { 0.0 }
+}
+
+// -----------------------------------------------------------------------------
+// Recording
+// -----------------------------------------------------------------------------
+impl MetricsHistory {
+    /// Reads both tiers back on first use; needs the app handle for the data dir.
+    pub fn ensure_loaded(&mut self, app_handle: &AppHandle<Wry>) {
+        if self.loaded {
+            return;
+        }
+        self.loaded = true;
+        self.dir = app_handle.path().app_data_dir().ok();
+        let Some(dir) = self.dir.clone() else { return };
+        let now = now_ms();
+
+        if let Some(points) = fs::read_to_string(dir.join(FINE_FILE))
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Vec<MetricPoint>>(&raw).ok())
+        {
+            self.fine = points.into_iter().filter(|p| p.t >= now - FINE_RETENTION_MS).collect();
+        }
+
+        let raw = fs::read_to_string(dir.join(COARSE_FILE)).unwrap_or_default();
+        let total_lines = raw.lines().count();
+        self.coarse = raw
+            .lines()
+            .filter_map(|line| serde_json::from_str::<MetricPoint>(line).ok())
+            .filter(|p| p.t >= now - COARSE_RETENTION_MS)
+            .collect();
+        if total_lines > self.coarse.len() {
+            self.rewrite_coarse();
+        }
+    }
+
+    pub fn record(&mut self, data: &SystemData) {
+        let now = now_ms();
+        if self.fine.back().is_some_and(|p| now - p.t < FINE_STEP_MS) {
+            return;
+        }
+
+        let rx: u64 = data.networks.iter().map(|n| n.total_received_bytes).sum();
+        let tx: u64 = data.networks.iter().map(|n| n.total_transmitted_bytes).sum();
+        let (rx_rate, tx_rate) = match self.previous_totals {
+            // After a pause the average over the gap would be meaningless.
+            Some((t, prev_rx, prev_tx)) if now > t && now - t <= 10 * FINE_STEP_MS => {
+                let secs = (now - t) as f64 / 1_000.0;
+                (rx.saturating_sub(prev_rx) as f64 / secs, tx.saturating_sub(prev_tx) as f64 / secs)
+            }
+            _ => (0.0, 0.0),
+        };
+        self.previous_totals = Some((now, rx, tx));
+
+        let point = MetricPoint {
+            t: now,
+            cpu_percent: data.cpu.global_usage_percent,
+            memory_used_percent: percent(data.memory.used_kb as f64, data.memory.total_kb as f64),
+            swap_used_percent: percent(data.memory.used_swap_kb as f64, data.memory.total_swap_kb as f64),
+            net_rx_bytes_per_s: rx_rate,
+            net_tx_bytes_per_s: tx_rate,
+            disk_used_percent: data
+                .disks
+                .iter()
+                .map(|d| (d.mount_point.clone(), percent(d.total_gb - d.available_gb, d.total_gb)))
+                .collect(),
+            temperatures_c: data
+                .sensors
+                .iter()
+                .filter(|s| s.kind == "temperature")
+                .map(|s| (s.id.clone(), s.value as f32))
+                .collect(),
+        };
+
+        // A finished minute becomes one coarse point.
+        if self.current_minute.first().is_some_and(|p| p.t / COARSE_STEP_MS != now / COARSE_STEP_MS) {
+            let minute = average(&std::mem::take(&mut self.current_minute));
+            self.append_coarse(&minute);
+            self.coarse.push_back(minute);
+            while self.coarse.front().is_some_and(|p| p.t < now - COARSE_RETENTION_MS) {
+                self.coarse.pop_front();
+            }
+        }
+        self.current_minute.push(point.clone());
+
+        self.fine.push_back(point);
+        while self.fine.front().is_some_and(|p| p.t < now - FINE_RETENTION_MS) {
+            self.fine.pop_front();
+        }
+
+        if now - self.last_fine_flush >= FINE_FLUSH_MS {
+            self.last_fine_flush = now;
+            self.write_fine();
+        }
+    }
+
+    /// The 1 s tier answers ranges no longer than it keeps that reach into it. This
+    /// does not compare against the clock, so the default "last 10 minutes" range,
+    /// computed a moment before, still gets 1 s points.
+    pub fn query(&self, from: i64, to: i64) -> MetricsSeries {
+        let use_fine = to - from <= FINE_RETENTION_MS && self.fine.front().is_some_and(|oldest| to >= oldest.t);
+        let (tier, resolution_s) = if use_fine { (&self.fine, 1) } else { (&self.coarse, 60) };
+
+        let mut points: Vec<MetricPoint> = tier.iter().filter(|p| p.t >= from && p.t <= to).cloned().collect();
+        // The minute in progress is not in the coarse tier yet.
+        if !use_fine && !self.current_minute.is_empty() {
+            let partial = average(&self.current_minute);
+            if partial.t >= from && partial.t <= to {
+                points.push(partial);
+            }
+        }
+
+        MetricsSeries { resolution_s, from, to, points }
+    }
+
+    // -------------------------------------------------------------------------
+    // Persistence: failures only cost history, so they are logged and ignored
+    // -------------------------------------------------------------------------
+    fn ensure_dir(&self) -> Option<&PathBuf> {
+        let dir = self.dir.as_ref()?;
+        if let Err(e) = fs::create_dir_all(dir) {
+            eprintln!("Failed to create {}: {}", dir.display(), e);
+            return None;
+        }
+        Some(dir)
+    }
+
+    fn write_fine(&self) {
+        let Some(dir) = self.ensure_dir() else { return };
+        let points: Vec<&MetricPoint> = self.fine.iter().collect();
+        if let Err(e) = serde_json::to_string(&points)
+            .map_err(|e| e.to_string())
+            .and_then(|json| fs::write(dir.join(FINE_FILE), json).map_err(|e| e.to_string()))
+        {
+            eprintln!("Failed to write {}: {}", FINE_FILE, e);
+        }
+    }
+
+    fn append_coarse(&self, point: &MetricPoint) {
+        let Some(dir) = self.ensure_dir() else { return };
+        let result = serde_json::to_string(point).map_err(|e| e.to_string()).and_then(|line| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(dir.join(COARSE_FILE))
+                .and_then(|mut f| writeln!(f, "{}", line))
+                .map_err(|e| e.to_string())
+        });
+        if let Err(e) = result {
+            eprintln!("Failed to append to {}: {}", COARSE_FILE, e);
+        }
+    }
+
+    fn rewrite_coarse(&self) {
+        let Some(dir) = self.ensure_dir() else { return };
+        let content: String = self
+            .coarse
+            .iter()
+            .filter_map(|p| serde_json::to_string(p).ok())
+            .map(|line| line + "\n")
+            .collect();
+        if let Err(e) = fs::write(dir.join(COARSE_FILE), content) {
+            eprintln!("Failed to write {}: {}", COARSE_FILE, e);
+        }
+    }
+}
+
+/// Mean of every field; map entries are averaged over the points that have them.
+fn average(points: &[MetricPoint]) -> MetricPoint {
+    let n = points.len().max(1) as f64;
+    let mean = |f: &dyn Fn(&MetricPoint) -> f64| points.iter().map(f).sum::<f64>() / n;
+    let mean_map = |f: &dyn Fn(&MetricPoint) -> &BTreeMap<String, f32>| {
+        let mut sums: BTreeMap<String, (f64, usize)> = BTreeMap::new();
+        for (key, value) in points.iter().flat_map(f) {
+            let entry = sums.entry(key.clone()).or_default();
+            entry.0 += *value as f64;
+            entry.1 += 1;
+        }
+        sums.into_iter().map(|(k, (sum, count))| (k, (sum / count as f64) as f32)).collect()
+    };
+
+    MetricPoint {
+        t: points.first().map(|p| p.t / COARSE_STEP_MS * COARSE_STEP_MS).unwrap_or(0),
+        cpu_percent: mean(&|p| p.cpu_percent as f64) as f32,
+        memory_used_percent: mean(&|p| p.memory_used_percent as f64) as f32,
+        swap_used_percent: mean(&|p| p.swap_used_percent as f64) as f32,
+        net_rx_bytes_per_s: mean(&|p| p.net_rx_bytes_per_s),
+        net_tx_bytes_per_s: mean(&|p| p.net_tx_bytes_per_s),
+        disk_used_percent: mean_map(&|p| &p.disk_used_percent),
+        temperatures_c: mean_map(&|p| &p.temperatures_c),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tauri command
+// -----------------------------------------------------------------------------
+
+/// Points between `from` and `to` (Unix ms, default: the last 10 minutes up to now).
+/// Ranges reaching further back than the 1 s tier are answered from the 1 min tier.
+#[tauri::command]
+pub fn get_metrics_history(
+    app_handle: AppHandle<Wry>,
+    monitor: State<'_, SystemMonitor>,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<MetricsSeries, String> {
+    let to = to.unwrap_or_else(now_ms);
+    let from = from.unwrap_or(to - FINE_RETENTION_MS);
+    if from > to {
+        return Err("'from' must not be after 'to'.".into());
+    }
+
+    let history = monitor.history();
+    let mut history = history.lock().unwrap();
+    history.ensure_loaded(&app_handle);
+    Ok(history.query(from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(t: i64) -> MetricPoint {
+        MetricPoint { t, ..Default::default() }
+    }
+
+    fn history(now: i64) -> MetricsHistory {
+        MetricsHistory {
+            fine: (0..600).map(|i| point(now - 599_000 + i * FINE_STEP_MS)).collect(),
+            coarse: (1..=60).map(|i| point((now - i * COARSE_STEP_MS) / COARSE_STEP_MS * COARSE_STEP_MS)).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_range_uses_the_fine_tier() {
+        let now = now_ms();
+        let history = history(now);
+        // As computed by get_metrics_history, a moment before the query runs.
+        let series = history.query(now - FINE_RETENTION_MS, now);
+        assert_eq!(series.resolution_s, 1);
+        assert_eq!(series.points.len(), 600);
+    }
+
+    #[test]
+    fn tier_follows_the_requested_range() {
+        let now = now_ms();
+        let history = history(now);
+        let cases = [
+            // Last minute.
+            (now - 60_000, now, 1),
+            // Longer than the fine tier keeps.
+            (now - FINE_RETENTION_MS - 1, now, 60),
+            (now - 3_600_000, now, 60),
+            // Short, but before the oldest fine point.
+            (now - 1_800_000, now - 1_500_000, 60),
+        ];
+        for (from, to, resolution_s) in cases {
+            assert_eq!(history.query(from, to).resolution_s, resolution_s, "{}..{}", now - from, now - to);
+        }
+        assert_eq!(MetricsHistory::default().query(now - 60_000, now).resolution_s, 60);
+    }
+}
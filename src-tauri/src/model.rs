@@ -18,6 +18,9 @@ pub struct SystemData {
     pub processes: Vec<ProcessSnapshot>,
     pub components: Vec<ComponentSnapshot>,
     pub users: Vec<UserInfo>,
+    pub power: PowerInfo,
+    /// Every hwmon/RAPL reading; `components` keeps the sysinfo temperatures.
+    pub sensors: Vec<HwmonSensor>,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -59,6 +62,11 @@ pub struct CpuDetails {
     pub name: String,
     pub usage_percent: f32,
     pub frequency_mhz: u64,
+    /// Hardware limits and the current scaling window from cpufreq; None without cpufreq.
+    pub min_frequency_mhz: Option<u64>,
+    pub max_frequency_mhz: Option<u64>,
+    pub scaling_min_mhz: Option<u64>,
+    pub scaling_max_mhz: Option<u64>,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -77,6 +85,30 @@ pub struct NetworkData {
     pub total_received_bytes: u64,
     pub transmitted_bytes: u64,
     pub total_transmitted_bytes: u64,
+    /// Rates over the last refresh interval; 0 on the first sample.
+    pub received_bytes_per_s: f64,
+    pub transmitted_bytes_per_s: f64,
+    pub received_packets_per_s: f64,
+    pub transmitted_packets_per_s: f64,
+    pub addresses: Vec<InterfaceAddress>,
+    pub mtu: u64,
+    pub operstate: String,
+    pub speed_mbps: Option<u32>,
+    /// "full" or "half".
+    pub duplex: Option<String>,
+    pub wireless: bool,
+    /// The interface carries the (lowest metric) default route.
+    pub is_default_route: bool,
+    pub default_gateway_v4: Option<String>,
+    pub default_gateway_v6: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct InterfaceAddress {
+    pub address: String,
+    pub prefix: u8,
+    /// "ipv4" or "ipv6"
+    pub family: String,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -95,26 +127,119 @@ pub struct ComponentSnapshot {
     pub critical_c: Option<f32>,
 }
 
+#[derive(Serialize, Clone, Debug)]
+pub struct HwmonSensor {
+    /// "<chip>/<parent device>/<input>", e.g. "k10temp/0000:00:18.3/temp1",
+    /// "nvme/0000:01:00.0/temp1" (the controller's PCI address, not nvme0) or
+    /// "rapl/package-0"; stable across reboots, unlike hwmonN.
+    pub id: String,
+    /// Driver name: "coretemp", "nct6798", "amdgpu", "nvme", "rapl", ...
+    pub chip: String,
+    /// "temperature", "fan", "voltage", "current" or "power"
+    pub kind: String,
+    pub label: String,
+    pub value: f64,
+    /// "°C", "RPM", "V", "A" or "W"
+    pub unit: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub critical: Option<f64>,
+}
+
 #[derive(Serialize, Clone, Debug)]
 pub struct UserInfo {
     pub name: String,
     pub groups: Vec<String>,
 }
 
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct PowerInfo {
+    /// None on machines without a mains adapter (desktops report none, or only UPS/USB).
+    pub on_ac: Option<bool>,
+    pub adapters: Vec<PowerAdapter>,
+    pub batteries: Vec<BatteryInfo>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PowerAdapter {
+    pub name: String,
+    /// "Mains", "USB", ...
+    pub kind: String,
+    pub online: bool,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct BatteryInfo {
+    pub name: String,
+    /// False for peripherals (mice, headsets) that report a battery over HID or Bluetooth.
+    pub system: bool,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    pub technology: Option<String>,
+    /// "Charging", "Discharging", "Full", "Not charging", "Unknown"
+    pub status: String,
+    pub capacity_percent: Option<f32>,
+    pub energy_now_wh: Option<f64>,
+    pub energy_full_wh: Option<f64>,
+    pub energy_full_design_wh: Option<f64>,
+    /// Full vs design capacity; 100 minus this is the wear.
+    pub health_percent: Option<f32>,
+    pub cycle_count: Option<u32>,
+    /// Charge or discharge rate, always positive; `status` gives the direction.
+    pub power_w: Option<f64>,
+    pub voltage_v: Option<f64>,
+    pub time_to_empty_s: Option<u64>,
+    pub time_to_full_s: Option<u64>,
+    /// Charging starts below / stops at these percentages, where the driver supports it.
+    pub charge_start_threshold: Option<u8>,
+    pub charge_end_threshold: Option<u8>,
+}
+
 // --- Kernel Info Structures (from lib.rs) ---
 
 #[derive(Debug, Serialize)]
 pub struct KernelInfo {
     pub running_kernel: String,
+    pub running_kernel_lifecycle: String,
+    pub running_kernel_eol: bool,
+    pub running_kernel_eol_date: Option<String>,
+    pub recommended_kernel: Option<String>, // Newest LTS package available in the repos
+    pub lifecycle_data_updated: String,
     pub installed_kernels: Vec<InstalledKernel>,
     pub installable_kernels: Vec<InstallableKernel>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct InstalledKernel {
-    pub name: String,
-    pub version: String,
+    pub name: String,        // Owning package, e.g. "linux-lts"
+    pub version: String,     // Exact package version, e.g. "6.6.30-1"
     pub flavor: String,
+    pub pkgbase: Option<String>,
+    pub kernel_release: String, // Directory name in /usr/lib/modules, matches `uname -r`
+    pub headers_package: Option<String>,
+    pub headers_installed: bool,
+    pub running: bool,
+    pub orphaned: bool,      // Module tree left behind after the package was removed
+}
+
+/// `dkms status` entry for one module and kernel release.
+#[derive(Debug, Serialize, Clone)]
+pub struct DkmsModuleStatus {
+    pub module: String,
+    pub module_version: String,
+    pub kernel_release: Option<String>,
+    pub status: String,      // "installed", "built", "added", or "missing" when never built for the kernel
+}
+
+#[derive(Debug, Serialize)]
+pub struct KernelOperationResult {
+    pub success: bool,
+    pub message: String,
+    pub package_name: String,
+    pub headers_package: Option<String>,
+    pub kernel_release: Option<String>,
+    pub dkms_modules: Vec<DkmsModuleStatus>,
 }
 
 #[derive(Debug, Serialize)]
@@ -123,6 +248,11 @@ pub struct InstallableKernel {
     pub version: String,
     pub description: String,
     pub flavor: String,
+    pub repository: String,
+    pub series: Option<String>,    // "6.12"
+    pub lifecycle: String,         // "mainline", "stable", "lts", "eol" or "unknown"
+    pub eol_date: Option<String>,
+    pub recommended: bool,
 }
 /// Event name for continuous device updates sent to the frontend.
 pub const BLUETOOTH_DEVICE_EVENT: &str = "bluetooth-device-update";
@@ -244,4 +374,17 @@ pub struct LocaleStatus {
     pub available_locales: Vec<String>,
     pub generated_locales: Vec<String>,
     pub reboot_required: bool,
+    #[serde(default)]
+    pub reboot_reasons: Vec<RebootReason>,
+}
+
+/// One thing that will only take effect after a reboot.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RebootReason {
+    /// "kernel-modules-missing", "package-upgraded", "locale-changed" or "flag-file"
+    pub kind: String,
+    pub message: String,
+    pub package: Option<String>,
+    /// RFC 3339 time of the change, when known.
+    pub changed_at: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,91 @@
+// src/network_link.rs
+use std::collections::HashMap;
+use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+/// Link state from /sys/class/net, which sysinfo does not report.
+pub struct LinkInfo {
+    /// "up", "down", "dormant", "unknown", ...
+    pub operstate: String,
+    /// None while the link is down or for virtual interfaces.
+    pub speed_mbps: Option<u32>,
+    pub duplex: Option<String>,
+    pub wireless: bool,
+}
+
+#[derive(Default)]
+pub struct DefaultRoute {
+    /// None for point-to-point links (VPNs) that route without a next hop.
+    pub gateway_v4: Option<String>,
+    pub gateway_v6: Option<String>,
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+pub fn link_info(interface: &str) -> LinkInfo {
+    let dir = Path::new("/sys/class/net").join(interface);
+    LinkInfo {
+        operstate: read_trimmed(&dir.join("operstate")).unwrap_or_else(|| "unknown".into()),
+        // Reading speed fails with EINVAL or yields -1 when there is no carrier.
+        speed_mbps: read_trimmed(&dir.join("speed"))
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|s| *s > 0)
+            .map(|s| s as u32),
+        duplex: read_trimmed(&dir.join("duplex")).filter(|d| d != "unknown"),
+        wireless: dir.join("wireless").exists() || dir.join("phy80211").exists(),
+    }
+}
+
+/// Interfaces carrying a default route, keyed by name. With several default routes
+/// per family, the one with the lowest metric wins.
+pub fn default_routes() -> HashMap<String, DefaultRoute> {
+    let mut routes: HashMap<String, DefaultRoute> = HashMap::new();
+
+    // Iface Destination Gateway Flags RefCnt Use Metric Mask ...
+    // Addresses are printed as the raw in-memory u32, i.e. little-endian on the hosts we run on.
+    let mut best_v4: Option<(u32, String, Option<String>)> = None;
+    for line in fs::read_to_string("/proc/net/route").unwrap_or_default().lines().skip(1) {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        let [iface, destination, gateway, _, _, _, metric, mask, ..] = cols[..] else { continue };
+        if destination != "00000000" || mask != "00000000" {
+            continue;
+        }
+        let Ok(metric) = metric.parse::<u32>() else { continue };
+        let gateway = u32::from_str_radix(gateway, 16)
+            .ok()
+            .filter(|g| *g != 0)
+            .map(|g| Ipv4Addr::from(g.to_le_bytes()).to_string());
+        if best_v4.as_ref().is_none_or(|(m, _, _)| metric < *m) {
+            best_v4 = Some((metric, iface.to_string(), gateway));
+        }
+    }
+    if let Some((_, iface, gateway)) = best_v4 {
+        routes.entry(iface).or_default().gateway_v4 = gateway;
+    }
+
+    // dest dest_prefix src src_prefix next_hop metric refcnt use flags iface
+    let mut best_v6: Option<(u32, String, Option<String>)> = None;
+    for line in fs::read_to_string("/proc/net/ipv6_route").unwrap_or_default().lines() {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        let [destination, prefix, _, _, next_hop, metric, _, _, _, iface] = cols[..] else { continue };
+        if iface == "lo" || prefix != "00" || destination.chars().any(|c| c != '0') {
+            continue;
+        }
+        let Ok(metric) = u32::from_str_radix(metric, 16) else { continue };
+        let gateway = u128::from_str_radix(next_hop, 16)
+            .ok()
+            .filter(|g| *g != 0)
+            .map(|g| Ipv6Addr::from(g).to_string());
+        if best_v6.as_ref().is_none_or(|(m, _, _)| metric < *m) {
+            best_v6 = Some((metric, iface.to_string(), gateway));
+        }
+    }
+    if let Some((_, iface, gateway)) = best_v6 {
+        routes.entry(iface).or_default().gateway_v6 = gateway;
+    }
+
+    routes
+}
@@ -0,0 +1,164 @@
+// src/package_footprint.rs
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::pacman_db::{run_pacman, LocalDb};
+
+/// Repository name reported for installed packages that no sync repo provides (AUR, local builds).
+const FOREIGN_REPO: &str = "local";
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Clone)]
+pub struct PackageFootprint {
+    pub name: String,
+    pub version: String,
+    pub repository: String,
+    pub explicit: bool,
+    pub installed_size_bytes: u64,
+    /// Dependencies nothing else requires; `pacman -Rs` would remove them too.
+    pub exclusive_dependencies: Vec<String>,
+    /// Own size plus the size of every exclusive dependency.
+    pub reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiskFootprintReport {
+    pub package_count: usize,
+    pub total_installed_bytes: u64,
+    pub packages: Vec<PackageFootprint>,
+}
+
+// -----------------------------------------------------------------------------
+// Tauri command: installed packages ranked by the space removing them frees
+// -----------------------------------------------------------------------------
+#[tauri::command]
+pub async fn get_disk_footprint(
+    explicit_only: Option<bool>,
+    repository: Option<String>,
+    limit: Option<usize>,
+) -> Result<DiskFootprintReport, String> {
+    let explicit_only = explicit_only.unwrap_or(false);
+    let db = LocalDb::load().await?;
+    let repos = installed_repositories().await;
+
+    let total_installed_bytes = db.packages.values().map(|p| p.installed_size_bytes).sum();
+
+    let mut packages: Vec<PackageFootprint> = db
+        .packages
+        .values()
+        .filter(|pkg| !explicit_only || pkg.explicit)
+        .map(|pkg| {
+            let repo = repos
+                .get(&pkg.name)
+                .cloned()
+                .unwrap_or_else(|| FOREIGN_REPO.to_string());
+            (pkg, repo)
+        })
+        .filter(|(_, repo)| repository.as_deref().is_none_or(|wanted| wanted == repo))
+        .map(|(pkg, repo)| {
+            let mut exclusive: Vec<String> = removal_set(&db, &pkg.name)
+                .into_iter()
+                .filter(|name| name != &pkg.name)
+                .collect();
+            exclusive.sort();
+
+            let reclaimable_bytes = pkg.installed_size_bytes
+                + exclusive
+                    .iter()
+                    .filter_map(|name| db.get(name))
+                    .map(|dep| dep.installed_size_bytes)
+                    .sum::<u64>();
+
+            PackageFootprint {
+                name: pkg.name.clone(),
+                version: pkg.version.clone(),
+                repository: repo,
+                explicit: pkg.explicit,
+                installed_size_bytes: pkg.installed_size_bytes,
+                exclusive_dependencies: exclusive,
+                reclaimable_bytes,
+            }
+        })
+        .collect();
+
+    packages.sort_by(|a, b| {
+        b.reclaimable_bytes
+            .cmp(&a.reclaimable_bytes)
+            .then_with(|| b.installed_size_bytes.cmp(&a.installed_size_bytes))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let package_count = packages.len();
+    if let Some(limit) = limit {
+        packages.truncate(limit);
+    }
+
+    Ok(DiskFootprintReport {
+        package_count,
+        total_installed_bytes,
+        packages,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Helper: what `pacman -Rs <root>` would remove
+// -----------------------------------------------------------------------------
+/// Starts from every non-explicit package reachable from `root` and drops those
+/// required by a package outside the set until none is. Shrinking instead of growing
+/// keeps dependencies that only require each other (cycles) in the set.
+fn removal_set(db: &LocalDb, root: &str) -> HashSet<String> {
+    let mut removal: HashSet<String> = HashSet::from([root.to_string()]);
+    let mut queue = vec![root.to_string()];
+    while let Some(name) = queue.pop() {
+        let Some(pkg) = db.get(&name) else { continue };
+        for dep in pkg.depends_on.iter().filter_map(|d| db.resolve(d)) {
+            if !dep.explicit && removal.insert(dep.name.clone()) {
+                queue.push(dep.name.clone());
+            }
+        }
+    }
+
+    loop {
+        let needed: Vec<String> = removal
+            .iter()
+            .filter(|name| name.as_str() != root)
+            .filter_map(|name| db.get(name))
+            .filter(|pkg| pkg.required_by.iter().any(|r| !removal.contains(r)))
+            .map(|pkg| pkg.name.clone())
+            .collect();
+
+        if needed.is_empty() {
+            return removal;
+        }
+        for name in needed {
+            removal.remove(&name);
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper: map installed packages to their sync repository via `pacman -Sl`
+// -----------------------------------------------------------------------------
+async fn installed_repositories() -> HashMap<String, String> {
+    // "core bash 5.2.026-2 [installed]", repositories in pacman.conf order
+    let output = match run_pacman(&["-Sl"]).await {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Failed to list sync repositories: {}", e);
+            return HashMap::new();
+        }
+    };
+
+    // pacman installs from the first repository carrying a package (core before
+    // core-testing if listed first), so later ones must not overwrite it.
+    let mut repositories = HashMap::new();
+    for line in output.lines().filter(|line| line.contains("[installed")) {
+        let mut parts = line.split_whitespace();
+        if let (Some(repo), Some(name)) = (parts.next(), parts.next()) {
+            repositories.entry(name.to_string()).or_insert_with(|| repo.to_string());
+        }
+    }
+    repositories
+}
@@ -0,0 +1,317 @@
+// src/package_graph.rs
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::pacman_db::{query_sync_package, strip_version_constraint, LocalDb, PackageRecord};
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Clone)]
+pub struct DependencyNode {
+    pub name: String,
+    pub version: Option<String>,
+    pub installed: bool,
+    /// Installed explicitly rather than as a dependency.
+    pub explicit: bool,
+    /// Distance from the root package.
+    pub depth: usize,
+    /// Only reachable from the root through optional dependencies.
+    pub optional: bool,
+}
+
+/// An edge always points from the dependent package to its dependency,
+/// in both the forward and the reverse tree.
+#[derive(Debug, Serialize, Clone)]
+pub struct DependencyEdge {
+    pub from: String,
+    pub to: String,
+    /// The dependency string as declared, e.g. `glibc>=2.38` or `sh`.
+    pub requirement: String,
+    pub optional: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyGraph {
+    pub root: String,
+    pub reverse: bool,
+    pub max_depth: Option<usize>,
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+    /// Some nodes at `max_depth` have further dependencies that were not expanded.
+    pub truncated: bool,
+    /// Sync database lookups that failed; the packages named here are shown as
+    /// leaves and their own dependencies are missing from the graph.
+    pub lookup_errors: Vec<String>,
+}
+
+// -----------------------------------------------------------------------------
+// Tauri command: pactree-style dependency / reverse dependency graph
+// -----------------------------------------------------------------------------
+#[tauri::command]
+pub async fn get_dependency_tree(
+    package_name: String,
+    reverse: Option<bool>,
+    max_depth: Option<usize>,
+    include_optional: Option<bool>,
+) -> Result<DependencyGraph, String> {
+    let reverse = reverse.unwrap_or(false);
+    let include_optional = include_optional.unwrap_or(true);
+    let db = LocalDb::load().await?;
+
+    if reverse {
+        build_reverse_graph(&db, &package_name, max_depth, include_optional)
+    } else {
+        build_forward_graph(&db, &package_name, max_depth, include_optional).await
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Forward tree: what does this package pull in?
+// -----------------------------------------------------------------------------
+async fn build_forward_graph(
+    db: &LocalDb,
+    root: &str,
+    max_depth: Option<usize>,
+    include_optional: bool,
+) -> Result<DependencyGraph, String> {
+    // Packages that are not installed are looked up in the sync databases once.
+    let mut sync_cache: HashMap<String, Option<PackageRecord>> = HashMap::new();
+
+    let root_record = match db.get(root) {
+        Some(r) => r.clone(),
+        None => query_sync_package(root)
+            .await?
+            .ok_or_else(|| format!("Package '{}' not found", root))?,
+    };
+
+    let mut builder = GraphBuilder::new(root, max_depth);
+    builder.add_node(root_node(&root_record, db.get(root).is_some()));
+
+    let mut queue = VecDeque::from([(root_record, 0usize)]);
+    while let Some((record, depth)) = queue.pop_front() {
+        let mut deps: Vec<(&String, bool)> = record.depends_on.iter().map(|d| (d, false)).collect();
+        if include_optional {
+            deps.extend(record.optional_deps.iter().map(|d| (d, true)));
+        }
+        if deps.is_empty() {
+            continue;
+        }
+        if !builder.can_expand(depth) {
+            builder.truncated = true;
+            continue;
+        }
+
+        for (requirement, optional) in deps {
+            let (target, child) = match db.resolve(requirement) {
+                Some(pkg) => (pkg.name.clone(), Some((pkg.clone(), true))),
+                None => {
+                    let name = strip_version_constraint(requirement).to_string();
+                    if !sync_cache.contains_key(&name) {
+                        let found = match query_sync_package(&name).await {
+                            Ok(found) => found,
+                            Err(e) => {
+                                builder.lookup_errors.push(format!("{}: {}", name, e));
+                                None
+                            }
+                        };
+                        sync_cache.insert(name.clone(), found);
+                    }
+                    let child = sync_cache[&name].clone().map(|r| (r, false));
+                    (name, child)
+                }
+            };
+
+            builder.add_edge(&record.name, &target, requirement, optional);
+            if builder.contains(&target) {
+                continue;
+            }
+
+            match child {
+                Some((child_record, installed)) => {
+                    builder.add_node(DependencyNode {
+                        name: target,
+                        version: Some(child_record.version.clone()),
+                        installed,
+                        explicit: installed && child_record.explicit,
+                        depth: depth + 1,
+                        optional: false,
+                    });
+                    queue.push_back((child_record, depth + 1));
+                }
+                // Unknown to every database (e.g. a removed AUR dependency): keep it as a leaf.
+                None => builder.add_node(DependencyNode {
+                    name: target,
+                    version: None,
+                    installed: false,
+                    explicit: false,
+                    depth: depth + 1,
+                    optional: false,
+                }),
+            }
+        }
+    }
+
+    Ok(builder.finish(false))
+}
+
+// -----------------------------------------------------------------------------
+// Reverse tree: who needs this package?
+// -----------------------------------------------------------------------------
+fn build_reverse_graph(
+    db: &LocalDb,
+    root: &str,
+    max_depth: Option<usize>,
+    include_optional: bool,
+) -> Result<DependencyGraph, String> {
+    let root_record = db
+        .get(root)
+        .ok_or_else(|| format!("Package '{}' is not installed", root))?;
+
+    let mut builder = GraphBuilder::new(root, max_depth);
+    builder.add_node(root_node(root_record, true));
+
+    let mut queue = VecDeque::from([(root_record, 0usize)]);
+    while let Some((record, depth)) = queue.pop_front() {
+        let mut dependents: Vec<(&String, bool)> =
+            record.required_by.iter().map(|d| (d, false)).collect();
+        if include_optional {
+            dependents.extend(record.optional_for.iter().map(|d| (d, true)));
+        }
+        if dependents.is_empty() {
+            continue;
+        }
+        if !builder.can_expand(depth) {
+            builder.truncated = true;
+            continue;
+        }
+
+        for (dependent, optional) in dependents {
+            let Some(parent) = db.get(dependent) else { continue };
+
+            // Keep the requirement string the dependent actually declared.
+            let declared = parent
+                .depends_on
+                .iter()
+                .chain(parent.optional_deps.iter())
+                .find(|d| db.resolve(d).map(|p| p.name == record.name).unwrap_or(false))
+                .cloned()
+                .unwrap_or_else(|| record.name.clone());
+
+            builder.add_edge(&parent.name, &record.name, &declared, optional);
+            if builder.contains(&parent.name) {
+                continue;
+            }
+
+            builder.add_node(DependencyNode {
+                name: parent.name.clone(),
+                version: Some(parent.version.clone()),
+                installed: true,
+                explicit: parent.explicit,
+                depth: depth + 1,
+                optional: false,
+            });
+            queue.push_back((parent, depth + 1));
+        }
+    }
+
+    Ok(builder.finish(true))
+}
+
+// -----------------------------------------------------------------------------
+// Helper: shared node / edge bookkeeping
+// -----------------------------------------------------------------------------
+struct GraphBuilder {
+    root: String,
+    max_depth: Option<usize>,
+    nodes: Vec<DependencyNode>,
+    edges: Vec<DependencyEdge>,
+    seen_nodes: HashSet<String>,
+    seen_edges: HashSet<(String, String)>,
+    truncated: bool,
+    lookup_errors: Vec<String>,
+}
+
+impl GraphBuilder {
+    fn new(root: &str, max_depth: Option<usize>) -> Self {
+        GraphBuilder {
+            root: root.to_string(),
+            max_depth,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            seen_nodes: HashSet::new(),
+            seen_edges: HashSet::new(),
+            truncated: false,
+            lookup_errors: Vec::new(),
+        }
+    }
+
+    fn can_expand(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max| depth < max)
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.seen_nodes.contains(name)
+    }
+
+    fn add_node(&mut self, node: DependencyNode) {
+        if self.seen_nodes.insert(node.name.clone()) {
+            self.nodes.push(node);
+        }
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str, requirement: &str, optional: bool) {
+        if self.seen_edges.insert((from.to_string(), to.to_string())) {
+            self.edges.push(DependencyEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                requirement: requirement.to_string(),
+                optional,
+            });
+        }
+    }
+
+    /// Marks nodes that cannot be reached from the root through hard dependencies only.
+    fn finish(mut self, reverse: bool) -> DependencyGraph {
+        let mut required: HashSet<&str> = HashSet::from([self.root.as_str()]);
+        let mut queue = VecDeque::from([self.root.as_str()]);
+        while let Some(current) = queue.pop_front() {
+            for edge in self.edges.iter().filter(|e| !e.optional) {
+                let (from, to) = if reverse {
+                    (edge.to.as_str(), edge.from.as_str())
+                } else {
+                    (edge.from.as_str(), edge.to.as_str())
+                };
+                if from == current && required.insert(to) {
+                    queue.push_back(to);
+                }
+            }
+        }
+        let required: HashSet<String> = required.into_iter().map(|s| s.to_string()).collect();
+
+        for node in &mut self.nodes {
+            node.optional = !required.contains(&node.name);
+        }
+
+        DependencyGraph {
+            root: self.root,
+            reverse,
+            max_depth: self.max_depth,
+            nodes: self.nodes,
+            edges: self.edges,
+            truncated: self.truncated,
+            lookup_errors: self.lookup_errors,
+        }
+    }
+}
+
+fn root_node(record: &PackageRecord, installed: bool) -> DependencyNode {
+    DependencyNode {
+        name: record.name.clone(),
+        version: Some(record.version.clone()),
+        installed,
+        explicit: installed && record.explicit,
+        depth: 0,
+        optional: false,
+    }
+}
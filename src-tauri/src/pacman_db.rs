@@ -0,0 +1,205 @@
+// src/pacman_db.rs
+use std::collections::HashMap;
+use tokio::process::Command;
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+/// One package record as printed by `pacman -Qi` (local) or `pacman -Si` (sync).
+#[derive(Debug, Clone, Default)]
+pub struct PackageRecord {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    /// Only filled by `pacman -Si`; the local database does not know the repo.
+    pub repository: Option<String>,
+    pub provides: Vec<String>,
+    pub depends_on: Vec<String>,
+    /// Package names only, the "description [installed]" suffix is dropped.
+    pub optional_deps: Vec<String>,
+    pub required_by: Vec<String>,
+    pub optional_for: Vec<String>,
+    pub installed_size_bytes: u64,
+    pub explicit: bool,
+}
+
+/// Snapshot of the local package database with a provider index, so that
+/// dependency strings like `sh` or `libreadline.so=8-64` resolve to packages.
+pub struct LocalDb {
+    pub packages: HashMap<String, PackageRecord>,
+    providers: HashMap<String, Vec<String>>,
+}
+
+impl LocalDb {
+    pub async fn load() -> Result<Self, String> {
+        let output = run_pacman(&["-Qi"]).await?;
+        Ok(Self::from_records(parse_info_output(&output)))
+    }
+
+    pub fn from_records(records: Vec<PackageRecord>) -> Self {
+        let mut providers: HashMap<String, Vec<String>> = HashMap::new();
+        for record in &records {
+            for provided in &record.provides {
+                providers
+                    .entry(strip_version_constraint(provided).to_string())
+                    .or_default()
+                    .push(record.name.clone());
+            }
+        }
+
+        let packages = records.into_iter().map(|r| (r.name.clone(), r)).collect();
+        LocalDb { packages, providers }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PackageRecord> {
+        self.packages.get(name)
+    }
+
+    /// Resolves a dependency string to the installed package satisfying it.
+    pub fn resolve(&self, dependency: &str) -> Option<&PackageRecord> {
+        let name = strip_version_constraint(dependency);
+        if let Some(pkg) = self.packages.get(name) {
+            return Some(pkg);
+        }
+        self.providers
+            .get(name)
+            .and_then(|names| names.iter().find_map(|n| self.packages.get(n)))
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper: run pacman with a stable (English) output format
+// -----------------------------------------------------------------------------
+pub async fn run_pacman(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("pacman")
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute pacman: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!(
+            "pacman {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Looks a package up in the sync databases. Returns `Ok(None)` if no repo has it.
+pub async fn query_sync_package(name: &str) -> Result<Option<PackageRecord>, String> {
+    match run_pacman(&["-Si", name]).await {
+        Ok(output) => Ok(parse_info_output(&output).into_iter().next()),
+        Err(e) if e.contains("was not found") => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Parsing
+// -----------------------------------------------------------------------------
+
+/// `glibc>=2.38` → `glibc`, `libreadline.so=8-64` → `libreadline.so`
+pub fn strip_version_constraint(dependency: &str) -> &str {
+    dependency
+        .split(['<', '>', '='])
+        .next()
+        .unwrap_or(dependency)
+        .trim()
+}
+
+/// Parses the "Key : value" blocks of `pacman -Qi` / `pacman -Si`.
+/// Records are separated by blank lines, continuation lines are indented.
+pub fn parse_info_output(output: &str) -> Vec<PackageRecord> {
+    let mut records = Vec::new();
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            if !fields.is_empty() {
+                records.push(record_from_fields(&fields));
+                fields.clear();
+            }
+            continue;
+        }
+
+        if line.starts_with(char::is_whitespace) {
+            if let Some((_, value)) = fields.last_mut() {
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+        } else if let Some((key, value)) = line.split_once(" : ") {
+            fields.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    if !fields.is_empty() {
+        records.push(record_from_fields(&fields));
+    }
+
+    records
+}
+
+fn record_from_fields(fields: &[(String, String)]) -> PackageRecord {
+    let mut record = PackageRecord::default();
+
+    for (key, value) in fields {
+        match key.as_str() {
+            "Name" => record.name = value.clone(),
+            "Version" => record.version = value.clone(),
+            "Description" => record.description = value.clone(),
+            "Repository" => record.repository = Some(value.clone()),
+            "Provides" => record.provides = parse_list(value),
+            "Depends On" => record.depends_on = parse_list(value),
+            "Optional Deps" => record.optional_deps = parse_optional_deps(value),
+            "Required By" => record.required_by = parse_list(value),
+            "Optional For" => record.optional_for = parse_list(value),
+            "Installed Size" => record.installed_size_bytes = parse_size(value).unwrap_or(0),
+            "Install Reason" => record.explicit = value.starts_with("Explicitly"),
+            _ => {}
+        }
+    }
+
+    record
+}
+
+fn parse_list(value: &str) -> Vec<String> {
+    if value == "None" {
+        return Vec::new();
+    }
+    value.split_whitespace().map(|s| s.to_string()).collect()
+}
+
+// "python-foo: for bar support [installed]" → "python-foo"
+fn parse_optional_deps(value: &str) -> Vec<String> {
+    if value == "None" {
+        return Vec::new();
+    }
+    value
+        .lines()
+        .filter_map(|line| {
+            line.split([':', ' '])
+                .next()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        })
+        .collect()
+}
+
+/// "9.32 MiB" → bytes
+pub fn parse_size(value: &str) -> Option<u64> {
+    let mut parts = value.split_whitespace();
+    let number: f64 = parts.next()?.parse().ok()?;
+    let multiplier = match parts.next().unwrap_or("B") {
+        "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024_f64.powi(2),
+        "GiB" => 1024_f64.powi(3),
+        "TiB" => 1024_f64.powi(4),
+        _ => return None,
+    };
+    Some((number * multiplier).round() as u64)
+}
@@ -1,5 +1,6 @@
 use serde::Serialize;
 use std::process::Stdio;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
 use tauri::{AppHandle, Emitter};
 use tokio::io::{AsyncBufReadExt, BufReader};
@@ -10,6 +11,8 @@ use tokio::fs::File;
 use chrono::{DateTime, Utc};
 use futures::future;
 
+use crate::snapshot;
+
 // -----------------------------------------------------------------------------
 // Configuration
 // -----------------------------------------------------------------------------
@@ -26,6 +29,8 @@ pub struct PacmanResult {
     pub message: String,
     pub operation: String,
     pub package_name: Option<String>,
+    /// Snapshot taken before the transaction, if one was requested.
+    pub snapshot_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -59,7 +64,7 @@ pub struct SystemUpdateStatus {
 // -----------------------------------------------------------------------------
 // Helper: emit progress to the frontend
 // -----------------------------------------------------------------------------
-fn emit_progress(handle: &AppHandle, step: &str, detail: &str) {
+pub(crate) fn emit_progress(handle: &AppHandle, step: &str, detail: &str) {
     let progress = PacmanProgress {
         current_step: step.to_string(),
         detail: detail.to_string(),
@@ -71,12 +76,33 @@ fn emit_progress(handle: &AppHandle, step: &str, detail: &str) {
 // -----------------------------------------------------------------------------
 // Helper: run a command with streamed output + timeout
 // -----------------------------------------------------------------------------
-async fn run_command_with_output(
+pub(crate) struct StreamedOutput {
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// Lines from both streams, in the order they arrived.
+    pub lines: Vec<String>,
+}
+
+/// Runs a command with its output streamed as progress events and returns the exit
+/// code and the interleaved output, so callers can inspect what the tool printed.
+pub(crate) async fn run_command_with_status(
+    program: &str,
+    args: &[&str],
+    app_handle: &AppHandle,
+    op_desc: &str,
+) -> Result<StreamedOutput, String> {
+    run_command_with_timeout(program, args, app_handle, op_desc, COMMAND_TIMEOUT).await
+}
+
+/// `run_command_with_status` for tools that need longer than a pacman operation.
+pub(crate) async fn run_command_with_timeout(
     program: &str,
     args: &[&str],
     app_handle: &AppHandle,
     op_desc: &str,
-) -> Result<(String, String), String> {
+    limit: Duration,
+) -> Result<StreamedOutput, String> {
     let prog = program.to_string();
     let args_str = args.join(" ");
     emit_progress(app_handle, op_desc, &format!("Running: {} {}", prog, args_str));
@@ -97,31 +123,45 @@ async fn run_command_with_output(
 
     let out_handle = app_handle.clone();
     let err_handle = app_handle.clone();
+    let all_lines = Arc::new(Mutex::new(Vec::new()));
+    let out_lines = all_lines.clone();
+    let err_lines = all_lines.clone();
 
     let stdout_task = tokio::spawn(async move {
+        let mut collected = Vec::new();
         while let Ok(Some(line)) = stdout_reader.next_line().await {
             emit_progress(&out_handle, "STDOUT", &line);
+            out_lines.lock().unwrap().push(line.clone());
+            collected.push(line);
         }
+        collected
     });
 
     let stderr_task = tokio::spawn(async move {
+        let mut collected = Vec::new();
         while let Ok(Some(line)) = stderr_reader.next_line().await {
             emit_progress(&err_handle, "STDERR", &line);
+            err_lines.lock().unwrap().push(line.clone());
+            collected.push(line);
         }
+        collected
     });
 
     // Wait for the child with timeout
-    let output = timeout(COMMAND_TIMEOUT, child.wait_with_output())
+    let status = timeout(limit, child.wait())
         .await
-        .map_err(|_| format!("Command timed out after {:?}", COMMAND_TIMEOUT))?
+        .map_err(|_| format!("Command timed out after {:?}", limit))?
         .map_err(|e| e.to_string())?;
 
-    let _ = tokio::join!(stdout_task, stderr_task);
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let (stdout, stderr) = tokio::join!(stdout_task, stderr_task);
+    let lines = std::mem::take(&mut *all_lines.lock().unwrap());
 
-    Ok((stdout, stderr))
+    Ok(StreamedOutput {
+        exit_code: status.code(),
+        stdout: stdout.unwrap_or_default().join("\n"),
+        stderr: stderr.unwrap_or_default().join("\n"),
+        lines,
+    })
 }
 
 // -----------------------------------------------------------------------------
@@ -132,6 +172,7 @@ pub async fn manage_pacman_package(
     app_handle: AppHandle,
     operation: String,
     package_name: Option<String>,
+    snapshot: Option<bool>,
 ) -> String {
     // Note: The logic for "update" here is now primarily handled by run_system_update,
     // but kept here for backward compatibility/simplicity of single package update if needed.
@@ -146,6 +187,7 @@ pub async fn manage_pacman_package(
                 message: "Package name required for install/remove.".into(),
                 operation,
                 package_name: None,
+                snapshot_id: None,
             })
             .to_string();
         },
@@ -170,15 +212,56 @@ pub async fn manage_pacman_package(
                 message: format!("Invalid operation: {}", operation),
                 operation,
                 package_name: original_pkg,
+                snapshot_id: None,
             })
             .to_string();
         }
     };
 
+    // Optional restore point before a full upgrade
+    let mut snapshot_id: Option<String> = None;
+    if operation == "update" && snapshot.unwrap_or(false) {
+        match snapshot::create_pre_transaction_snapshot(&app_handle, &operation).await {
+            Ok(record) => snapshot_id = Some(record.snapshot_id),
+            Err(e) => {
+                let msg = format!("Pre-upgrade snapshot failed, update aborted: {}", e);
+                emit_progress(&app_handle, op_desc, &msg);
+                return json!(PacmanResult {
+                    success: false,
+                    message: msg,
+                    operation,
+                    package_name: original_pkg,
+                    snapshot_id: None,
+                })
+                .to_string();
+            }
+        }
+    }
+
     emit_progress(&app_handle, op_desc, &format!("Starting {}...", op_desc));
 
-    match run_command_with_output(program, &args_vec, &app_handle, op_desc).await {
-        Ok((_, _)) => {
+    let result = run_command_with_status(program, &args_vec, &app_handle, op_desc)
+        .await
+        .and_then(|output| match output.exit_code {
+            Some(0) => Ok(()),
+            Some(code @ (126 | 127)) => {
+                Err(format!("Root permission denied or cancelled by user. (Exit Code: {})", code))
+            }
+            code => Err(format!(
+                "{} failed. (Exit Code: {}) {}",
+                op_desc,
+                code.unwrap_or(-1),
+                output.stderr.trim()
+            )
+            .trim_end()
+            .to_string()),
+        });
+    if let Some(id) = &snapshot_id {
+        snapshot::record_transaction_result(&app_handle, id, result.is_ok());
+    }
+
+    match result {
+        Ok(()) => {
             let msg = format!("{} completed successfully.", op_desc);
             emit_progress(&app_handle, op_desc, &msg);
             json!(PacmanResult {
@@ -186,6 +269,7 @@ pub async fn manage_pacman_package(
                 message: msg,
                 operation,
                 package_name: original_pkg,
+                snapshot_id,
             })
             .to_string()
         }
@@ -196,6 +280,7 @@ pub async fn manage_pacman_package(
                 message: e,
                 operation,
                 package_name: original_pkg,
+                snapshot_id,
             })
             .to_string()
         }
@@ -0,0 +1,306 @@
+// src/power_profile.rs
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use zbus::zvariant::OwnedValue;
+use zbus::{Connection, Proxy};
+
+use crate::privileged::run_as_root;
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+const CPUFREQ: &str = "/sys/devices/system/cpu/cpufreq";
+const CPU: &str = "/sys/devices/system/cpu";
+/// power-profiles-daemon (and tuned-ppd) moved to the UPower namespace in 0.20;
+/// the old name is still exported for compatibility, but not by every version.
+const PPD_SERVICES: &[(&str, &str)] = &[
+    ("org.freedesktop.UPower.PowerProfiles", "/org/freedesktop/UPower/PowerProfiles"),
+    ("net.hadess.PowerProfiles", "/net/hadess/PowerProfiles"),
+];
+const TUNED: (&str, &str, &str) = ("com.redhat.tuned", "/Tuned", "com.redhat.tuned.control");
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Clone)]
+pub struct CpuPolicy {
+    /// "policy0"
+    pub name: String,
+    pub cpus: Vec<u32>,
+    pub governor: Option<String>,
+    pub available_governors: Vec<String>,
+    /// intel_pstate / amd-pstate in active mode only.
+    pub energy_performance_preference: Option<String>,
+    pub available_preferences: Vec<String>,
+    pub driver: Option<String>,
+    pub min_mhz: Option<u64>,
+    pub max_mhz: Option<u64>,
+    pub scaling_min_mhz: Option<u64>,
+    pub scaling_max_mhz: Option<u64>,
+    pub current_mhz: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PowerProfileStatus {
+    /// "power-profiles-daemon", "tuned" or "sysfs"
+    pub backend: String,
+    /// The daemon's profile; None for the sysfs backend.
+    pub active_profile: Option<String>,
+    pub available_profiles: Vec<String>,
+    pub policies: Vec<CpuPolicy>,
+}
+
+/// Hardware and current scaling limits of one CPU, in MHz.
+#[derive(Debug, Default)]
+pub struct FrequencyLimits {
+    pub min_mhz: Option<u64>,
+    pub max_mhz: Option<u64>,
+    pub scaling_min_mhz: Option<u64>,
+    pub scaling_max_mhz: Option<u64>,
+}
+
+enum Backend {
+    PowerProfilesDaemon(Proxy<'static>),
+    Tuned(Proxy<'static>),
+    Sysfs,
+}
+
+// -----------------------------------------------------------------------------
+// sysfs
+// -----------------------------------------------------------------------------
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// cpufreq reports kHz.
+fn read_mhz(path: &Path) -> Option<u64> {
+    read_trimmed(path)?.parse::<u64>().ok().map(|khz| khz / 1000)
+}
+
+fn read_list(path: &Path) -> Vec<String> {
+    read_trimmed(path).map(|s| s.split_whitespace().map(|w| w.to_string()).collect()).unwrap_or_default()
+}
+
+pub fn frequency_limits(cpu: usize) -> FrequencyLimits {
+    let dir = Path::new(CPU).join(format!("cpu{}", cpu)).join("cpufreq");
+    FrequencyLimits {
+        min_mhz: read_mhz(&dir.join("cpuinfo_min_freq")),
+        max_mhz: read_mhz(&dir.join("cpuinfo_max_freq")),
+        scaling_min_mhz: read_mhz(&dir.join("scaling_min_freq")),
+        scaling_max_mhz: read_mhz(&dir.join("scaling_max_freq")),
+    }
+}
+
+fn policies() -> Vec<CpuPolicy> {
+    let Ok(entries) = fs::read_dir(CPUFREQ) else { return Vec::new() };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|n| n.starts_with("policy"))
+        .collect();
+    names.sort_by_key(|n| n.trim_start_matches("policy").parse::<u32>().unwrap_or(u32::MAX));
+
+    names
+        .into_iter()
+        .map(|name| {
+            let dir = Path::new(CPUFREQ).join(&name);
+            CpuPolicy {
+                cpus: read_list(&dir.join("affected_cpus")).iter().filter_map(|c| c.parse().ok()).collect(),
+                governor: read_trimmed(&dir.join("scaling_governor")),
+                available_governors: read_list(&dir.join("scaling_available_governors")),
+                energy_performance_preference: read_trimmed(&dir.join("energy_performance_preference")),
+                available_preferences: read_list(&dir.join("energy_performance_available_preferences")),
+                driver: read_trimmed(&dir.join("scaling_driver")),
+                min_mhz: read_mhz(&dir.join("cpuinfo_min_freq")),
+                max_mhz: read_mhz(&dir.join("cpuinfo_max_freq")),
+                scaling_min_mhz: read_mhz(&dir.join("scaling_min_freq")),
+                scaling_max_mhz: read_mhz(&dir.join("scaling_max_freq")),
+                current_mhz: read_mhz(&dir.join("scaling_cur_freq")),
+                name,
+            }
+        })
+        .collect()
+}
+
+/// Writes `value` to `attribute` of every given policy in one pkexec call.
+/// Writes every (attribute, value) to every policy with one pkexec prompt, in order.
+/// When a write fails the ones before it are reverted, so no half-applied change stays.
+fn write_policies(policies: &[&CpuPolicy], writes: &[(&str, &str)]) -> Result<(), String> {
+    let pairs: Vec<String> = writes
+        .iter()
+        .flat_map(|(attribute, value)| {
+            policies.iter().flat_map(move |p| [format!("{}/{}/{}", CPUFREQ, p.name, attribute), value.to_string()])
+        })
+        .collect();
+    let mut args = vec!["-c", WRITE_POLICIES_SCRIPT, "sh"];
+    args.extend(pairs.iter().map(String::as_str));
+    run_as_root("sh", &args).map(|_| ())
+}
+
+/// Arguments are "file value" pairs; sysfs paths and governor/preference names
+/// contain no whitespace, so the undo list can be split on it.
+const WRITE_POLICIES_SCRIPT: &str = r#"undo=''
+revert() {
+    printf '%s' "$undo" | while read -r f v; do printf '%s' "$v" > "$f"; done
+    exit 1
+}
+while [ $# -ge 2 ]; do
+    old=$(cat "$1") || revert
+    printf '%s' "$2" > "$1" || revert
+    undo="$1 $old
+$undo"
+    shift 2
+done"#;
+
+// -----------------------------------------------------------------------------
+// Daemons
+// -----------------------------------------------------------------------------
+async fn detect_backend() -> Backend {
+    let Ok(conn) = Connection::system().await else { return Backend::Sysfs };
+
+    for (service, path) in PPD_SERVICES {
+        if let Ok(proxy) = Proxy::new(&conn, *service, *path, *service).await {
+            // Proxy creation is lazy; a property read tells whether the service exists.
+            if proxy.get_property::<String>("ActiveProfile").await.is_ok() {
+                return Backend::PowerProfilesDaemon(proxy);
+            }
+        }
+    }
+
+    let (service, path, interface) = TUNED;
+    if let Ok(proxy) = Proxy::new(&conn, service, path, interface).await {
+        if proxy.call::<_, _, String>("active_profile", &()).await.is_ok() {
+            return Backend::Tuned(proxy);
+        }
+    }
+    Backend::Sysfs
+}
+
+async fn status(backend: &Backend) -> Result<PowerProfileStatus, String> {
+    let (name, active_profile, available_profiles) = match backend {
+        Backend::PowerProfilesDaemon(proxy) => {
+            let active: String = proxy.get_property("ActiveProfile").await.map_err(|e| e.to_string())?;
+            let profiles: Vec<HashMap<String, OwnedValue>> = proxy.get_property("Profiles").await.unwrap_or_default();
+            let available = profiles
+                .iter()
+                .filter_map(|p| p.get("Profile").and_then(|v| <&str>::try_from(v).ok()).map(|s| s.to_string()))
+                .collect();
+            ("power-profiles-daemon", Some(active), available)
+        }
+        Backend::Tuned(proxy) => {
+            let active: String = proxy.call("active_profile", &()).await.map_err(|e| e.to_string())?;
+            let available: Vec<String> = proxy.call("profiles", &()).await.unwrap_or_default();
+            ("tuned", Some(active), available)
+        }
+        Backend::Sysfs => ("sysfs", None, Vec::new()),
+    };
+    Ok(PowerProfileStatus {
+        backend: name.to_string(),
+        active_profile,
+        available_profiles,
+        policies: policies(),
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Tauri commands
+// -----------------------------------------------------------------------------
+#[tauri::command]
+pub async fn get_power_profile() -> Result<PowerProfileStatus, String> {
+    status(&detect_backend().await).await
+}
+
+/// Switches the daemon's profile ("power-saver", "balanced", "performance" for
+/// power-profiles-daemon; any TuneD profile name). Both authorize through polkit.
+#[tauri::command]
+pub async fn set_power_profile(profile: String) -> Result<PowerProfileStatus, String> {
+    let backend = detect_backend().await;
+    let current = status(&backend).await?;
+    if !current.available_profiles.contains(&profile) {
+        return Err(format!("Unknown power profile '{}'.", profile));
+    }
+
+    match &backend {
+        Backend::PowerProfilesDaemon(proxy) => proxy
+            .set_property("ActiveProfile", profile.as_str())
+            .await
+            .map_err(|e| format!("Failed to switch to {}: {}", profile, e))?,
+        Backend::Tuned(proxy) => {
+            let (ok, message): (bool, String) = proxy
+                .call("switch_profile", &(profile.as_str(),))
+                .await
+                .map_err(|e| format!("Failed to switch to {}: {}", profile, e))?;
+            if !ok {
+                return Err(format!("TuneD refused {}: {}", profile, message));
+            }
+        }
+        Backend::Sysfs => {
+            return Err("No power profile daemon is running; set the CPU governor instead.".into());
+        }
+    }
+    status(&backend).await
+}
+
+/// Sets the governor and/or energy_performance_preference of the given policies
+/// (default: all) through pkexec. Refused while a daemon owns these settings,
+/// since it would undo the change on its next profile switch.
+#[tauri::command]
+pub async fn set_cpu_governor(
+    governor: Option<String>,
+    energy_performance_preference: Option<String>,
+    policies: Option<Vec<String>>,
+) -> Result<PowerProfileStatus, String> {
+    let backend = detect_backend().await;
+    if !matches!(backend, Backend::Sysfs) {
+        let current = status(&backend).await?;
+        return Err(format!("{} manages CPU scaling; switch its profile instead.", current.backend));
+    }
+    if governor.is_none() && energy_performance_preference.is_none() {
+        return Err("Nothing to change.".into());
+    }
+
+    let all = self::policies();
+    let selected: Vec<&CpuPolicy> = match &policies {
+        None => all.iter().collect(),
+        Some(names) => {
+            if let Some(unknown) = names.iter().find(|n| !all.iter().any(|p| &p.name == *n)) {
+                return Err(format!("Unknown cpufreq policy '{}'.", unknown));
+            }
+            all.iter().filter(|p| names.contains(&p.name)).collect()
+        }
+    };
+    if selected.is_empty() {
+        return Err("This system exposes no cpufreq policies.".into());
+    }
+
+    if let Some(governor) = &governor {
+        if let Some(p) = selected.iter().find(|p| !p.available_governors.contains(governor)) {
+            return Err(format!("{} does not support the '{}' governor.", p.name, governor));
+        }
+    }
+    if let Some(preference) = &energy_performance_preference {
+        if let Some(p) = selected.iter().find(|p| !p.available_preferences.contains(preference)) {
+            return Err(format!("{} does not support the '{}' preference.", p.name, preference));
+        }
+    }
+
+    let selected: Vec<CpuPolicy> = selected.into_iter().cloned().collect();
+    tokio::task::spawn_blocking(move || {
+        let selected: Vec<&CpuPolicy> = selected.iter().collect();
+        // The governor goes first: some drivers only accept a preference under powersave.
+        let writes: Vec<(&str, &str)> = [
+            governor.as_deref().map(|g| ("scaling_governor", g)),
+            energy_performance_preference.as_deref().map(|p| ("energy_performance_preference", p)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        write_policies(&selected, &writes)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    status(&backend).await
+}
@@ -0,0 +1,99 @@
+// src/power_supply.rs
+use std::fs;
+use std::path::Path;
+
+use crate::model::{BatteryInfo, PowerAdapter, PowerInfo};
+
+const POWER_SUPPLY: &str = "/sys/class/power_supply";
+
+fn read_trimmed(dir: &Path, attribute: &str) -> Option<String> {
+    fs::read_to_string(dir.join(attribute)).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// sysfs reports µWh, µAh, µW, µA and µV; this scales to the base unit.
+fn read_micro(dir: &Path, attribute: &str) -> Option<f64> {
+    read_trimmed(dir, attribute)?.parse::<f64>().ok().map(|v| v.abs() / 1_000_000.0)
+}
+
+fn read_number<T: std::str::FromStr>(dir: &Path, attribute: &str) -> Option<T> {
+    read_trimmed(dir, attribute)?.parse().ok()
+}
+
+fn battery(name: &str, dir: &Path) -> BatteryInfo {
+    let voltage_v = read_micro(dir, "voltage_now");
+    // Drivers report either energy (Wh) or charge (Ah); charge needs a voltage to compare.
+    let design_voltage = read_micro(dir, "voltage_min_design").or(voltage_v);
+    let energy = |energy_attr: &str, charge_attr: &str| {
+        read_micro(dir, energy_attr).or_else(|| Some(read_micro(dir, charge_attr)? * design_voltage?))
+    };
+    let energy_now_wh = energy("energy_now", "charge_now");
+    let energy_full_wh = energy("energy_full", "charge_full");
+    let energy_full_design_wh = energy("energy_full_design", "charge_full_design");
+    let power_w = read_micro(dir, "power_now")
+        .or_else(|| Some(read_micro(dir, "current_now")? * voltage_v?))
+        .filter(|p| *p > 0.0);
+
+    let status = read_trimmed(dir, "status").unwrap_or_else(|| "Unknown".into());
+    let hours_left = |wh: Option<f64>| Some((wh? / power_w?).max(0.0) * 3600.0).map(|s| s as u64);
+    let time_to_empty_s = if status == "Discharging" { hours_left(energy_now_wh) } else { None };
+    let time_to_full_s = if status == "Charging" {
+        hours_left(energy_full_wh.zip(energy_now_wh).map(|(full, now)| full - now))
+    } else {
+        None
+    };
+
+    BatteryInfo {
+        name: name.to_string(),
+        system: read_trimmed(dir, "scope").as_deref() != Some("Device"),
+        manufacturer: read_trimmed(dir, "manufacturer"),
+        model: read_trimmed(dir, "model_name"),
+        serial: read_trimmed(dir, "serial_number"),
+        technology: read_trimmed(dir, "technology"),
+        status,
+        capacity_percent: read_number(dir, "capacity")
+            .or_else(|| Some((energy_now_wh? / energy_full_wh? * 100.0) as f32)),
+        energy_now_wh,
+        energy_full_wh,
+        energy_full_design_wh,
+        health_percent: energy_full_wh
+            .zip(energy_full_design_wh)
+            .filter(|(_, design)| *design > 0.0)
+            .map(|(full, design)| (full / design * 100.0) as f32),
+        // Many drivers expose the file but always report 0.
+        cycle_count: read_number(dir, "cycle_count").filter(|c| *c > 0),
+        power_w,
+        voltage_v,
+        time_to_empty_s,
+        time_to_full_s,
+        // Older ThinkPad (tp_smapi era) kernels use the short names.
+        charge_start_threshold: read_number(dir, "charge_control_start_threshold")
+            .or_else(|| read_number(dir, "charge_start_threshold")),
+        charge_end_threshold: read_number(dir, "charge_control_end_threshold")
+            .or_else(|| read_number(dir, "charge_stop_threshold")),
+    }
+}
+
+pub fn power_info() -> PowerInfo {
+    let mut info = PowerInfo::default();
+    let Ok(entries) = fs::read_dir(POWER_SUPPLY) else { return info };
+    let mut names: Vec<String> = entries.filter_map(|e| e.ok()).map(|e| e.file_name().to_string_lossy().into_owned()).collect();
+    names.sort();
+
+    for name in names {
+        let dir = Path::new(POWER_SUPPLY).join(&name);
+        let kind = read_trimmed(&dir, "type").unwrap_or_default();
+        if kind == "Battery" {
+            info.batteries.push(battery(&name, &dir));
+        } else {
+            info.adapters.push(PowerAdapter {
+                online: read_trimmed(&dir, "online").as_deref() == Some("1"),
+                name,
+                kind,
+            });
+        }
+    }
+
+    let mains: Vec<&PowerAdapter> = info.adapters.iter().filter(|a| a.kind == "Mains").collect();
+    info.on_ac = (!mains.is_empty()).then(|| mains.iter().any(|a| a.online));
+    info
+}
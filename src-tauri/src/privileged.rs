@@ -0,0 +1,179 @@
+// src/privileged.rs
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+
+/// A config file edit shown to the user before it is written as root.
+#[derive(Debug, Serialize, Clone)]
+pub struct FileChange {
+    pub path: String,
+    pub original: String,
+    pub updated: String,
+    /// Changed lines only, prefixed with "-" / "+".
+    pub diff: Vec<String>,
+}
+
+impl FileChange {
+    pub fn new(path: &str, original: String, updated: String) -> Self {
+        let diff = line_diff(&original, &updated);
+        FileChange { path: path.to_string(), original, updated, diff }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.original == self.updated
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helpers: reading and writing root-owned files through pkexec
+// -----------------------------------------------------------------------------
+
+/// Reads a file, retrying through pkexec when it is only readable by root
+/// (grub.cfg, files on the ESP, ...).
+pub fn read_file(path: &str) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => run_as_root("cat", &[path]),
+        Err(e) => Err(format!("read {}: {}", path, e)),
+    }
+}
+
+/// Lists file names in a directory, retrying through pkexec when it is root-only.
+pub fn list_dir(path: &str) -> Result<Vec<String>, String> {
+    match fs::read_dir(path) {
+        Ok(entries) => Ok(entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            run_as_root("ls", &["-1", path]).map(|out| out.lines().map(|l| l.to_string()).collect())
+        }
+        Err(e) => Err(format!("read {}: {}", path, e)),
+    }
+}
+
+/// Replaces the contents of a root-owned file, keeping its owner and mode.
+/// The content goes through pkexec's stdin, so it never touches a world-writable
+/// directory where another user could swap the file before root reads it. It is
+/// written to a temporary file next to the target and renamed over it, so a full
+/// ESP or an interrupted write leaves the old file intact instead of a truncated one.
+pub fn write_file(path: &str, content: &str) -> Result<(), String> {
+    run_as_root_with_input("sh", &["-c", ATOMIC_WRITE_SCRIPT, "sh", path], content).map(|_| ())
+}
+
+/// New files get the 0644 that `cat >` would have created them with; modes are
+/// fixed on vfat, so failing to copy them is not an error.
+const ATOMIC_WRITE_SCRIPT: &str = r#"f=$(readlink -f -- "$1") || exit 1
+t=$(mktemp -- "$f.linuxhub-XXXXXX") || exit 1
+trap 'rm -f -- "$t"' EXIT
+cat > "$t" || exit 1
+if [ -e "$f" ]; then
+    chown --reference="$f" -- "$t" 2>/dev/null
+    chmod --reference="$f" -- "$t" 2>/dev/null
+else
+    chmod 644 -- "$t"
+fi
+sync -- "$t" && mv -f -- "$t" "$f""#;
+
+/// Runs a program through pkexec and returns its stdout.
+pub fn run_as_root(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("pkexec")
+        .arg(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to spawn pkexec process: {}", e))?;
+    root_output(program, output)
+}
+
+/// Like `run_as_root`, with `input` written to the program's stdin.
+pub fn run_as_root_with_input(program: &str, args: &[&str], input: &str) -> Result<String, String> {
+    let mut child = Command::new("pkexec")
+        .arg(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to spawn pkexec process: {}", e))?;
+
+    // Dropping stdin after the write closes it, so the program sees EOF.
+    let written = child.stdin.take().map(|mut stdin| stdin.write_all(input.as_bytes()));
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for pkexec process: {}", e))?;
+    let result = root_output(program, output)?;
+    // A cancelled authentication closes the pipe early; that case is reported above.
+    if let Some(Err(e)) = written {
+        return Err(format!("Failed to pass data to {}: {}", program, e));
+    }
+    Ok(result)
+}
+
+fn root_output(program: &str, output: Output) -> Result<String, String> {
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+
+    let exit_code = output.status.code().unwrap_or(-1);
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if exit_code == 126 || exit_code == 127 {
+        Err(format!("Root permission denied or cancelled by user. (Exit Code: {})", exit_code))
+    } else {
+        Err(format!("{} failed (Exit Code: {}): {}", program, exit_code, stderr))
+    }
+}
+
+/// Changed lines from a longest-common-subsequence diff, so an inserted line does not
+/// mark every following one as changed. Config files are small enough for the table.
+fn line_diff(original: &str, updated: &str) -> Vec<String> {
+    let old: Vec<&str> = original.lines().collect();
+    let new: Vec<&str> = updated.lines().collect();
+
+    // common[i][j]: length of the LCS of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+            diff.push(format!("-{}", old[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+{}", new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_reports_only_changed_lines() {
+        let original = "a\nb\nc\nd\n";
+        assert_eq!(line_diff(original, "a\nnew\nb\nc\nd\n"), ["+new"]);
+        assert_eq!(line_diff(original, "a\nc\nd\n"), ["-b"]);
+        assert_eq!(line_diff(original, "a\nB\nc\nd\ne\n"), ["-b", "+B", "+e"]);
+        assert!(line_diff(original, original).is_empty());
+        assert_eq!(line_diff("", "x\n"), ["+x"]);
+    }
+}
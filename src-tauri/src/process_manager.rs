@@ -0,0 +1,380 @@
+// src/process_manager.rs
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use sysinfo::{Process, ProcessRefreshKind, ProcessesToUpdate, System, Users};
+use tauri::State;
+
+use crate::privileged::run_as_root;
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+const SIGNALS: &[&str] = &["TERM", "KILL", "HUP", "INT", "QUIT", "STOP", "CONT", "USR1", "USR2"];
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Clone)]
+pub struct ProcessEntry {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub name: String,
+    pub user: Option<String>,
+    pub uid: Option<u32>,
+    pub status: String,
+    pub cpu_usage_percent: f32,
+    pub memory_rss_bytes: u64,
+    pub virtual_memory_bytes: u64,
+    pub cmdline: String,
+    pub exe: Option<String>,
+    /// Unix time in seconds.
+    pub start_time_s: u64,
+    pub run_time_s: u64,
+    pub nice: Option<i32>,
+    /// Rates since the previous listing; zero for processes of other users unless root.
+    pub disk_read_bytes_per_s: f64,
+    pub disk_write_bytes_per_s: f64,
+    pub disk_read_total_bytes: u64,
+    pub disk_write_total_bytes: u64,
+    /// Owned by the user running the app; anything else needs elevation to change.
+    pub owned: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProcessTreeNode {
+    #[serde(flatten)]
+    pub process: ProcessEntry,
+    pub children: Vec<ProcessTreeNode>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProcessList {
+    /// Every process before filtering.
+    pub total: usize,
+    /// Flat, sorted and filtered; empty when `tree` was requested.
+    pub processes: Vec<ProcessEntry>,
+    /// Roots of the parent/child forest; filtered processes keep their ancestors.
+    pub tree: Vec<ProcessTreeNode>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProcessActionResult {
+    pub success: bool,
+    pub message: String,
+    pub pid: u32,
+    pub elevated: bool,
+}
+
+/// sysinfo state plus the time of the last refresh, for CPU and disk rates.
+type ProcessState = (System, Users, Option<Instant>);
+
+/// Keeps one sysinfo System between listings so CPU usage and I/O rates are deltas.
+pub struct ProcessManager {
+    /// Shared with the blocking task that refreshes it.
+    state: Arc<Mutex<ProcessState>>,
+}
+
+impl Default for ProcessManager {
+    fn default() -> Self {
+        ProcessManager { state: Arc::new(Mutex::new((System::new(), Users::new_with_refreshed_list(), None))) }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Listing
+// -----------------------------------------------------------------------------
+
+/// Field 19 of /proc/<pid>/stat; the name in field 2 may contain spaces, so count from ')'.
+fn read_nice(pid: u32) -> Option<i32> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let after_name = &stat[stat.rfind(')')? + 1..];
+    after_name.split_whitespace().nth(16)?.parse().ok()
+}
+
+fn current_uid() -> Option<u32> {
+    fs::read_to_string("/proc/self/status")
+        .ok()?
+        .lines()
+        .find_map(|l| l.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn entry(process: &Process, users: &Users, own_uid: Option<u32>, elapsed_s: Option<f64>) -> ProcessEntry {
+    let pid = process.pid().as_u32();
+    let uid = process.user_id().map(|u| **u);
+    let disk = process.disk_usage();
+    let rate = |bytes: u64| elapsed_s.filter(|s| *s > 0.0).map(|s| bytes as f64 / s).unwrap_or(0.0);
+
+    ProcessEntry {
+        pid,
+        ppid: process.parent().map(|p| p.as_u32()),
+        name: process.name().to_string_lossy().into_owned(),
+        user: process
+            .user_id()
+            .and_then(|u| users.get_user_by_id(u))
+            .map(|u| u.name().to_string()),
+        uid,
+        status: format!("{:?}", process.status()),
+        cpu_usage_percent: process.cpu_usage(),
+        memory_rss_bytes: process.memory(),
+        virtual_memory_bytes: process.virtual_memory(),
+        cmdline: process
+            .cmd()
+            .iter()
+            .map(|a| a.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" "),
+        exe: process.exe().map(|p| p.to_string_lossy().into_owned()),
+        start_time_s: process.start_time(),
+        run_time_s: process.run_time(),
+        nice: read_nice(pid),
+        disk_read_bytes_per_s: rate(disk.read_bytes),
+        disk_write_bytes_per_s: rate(disk.written_bytes),
+        disk_read_total_bytes: disk.total_read_bytes,
+        disk_write_total_bytes: disk.total_written_bytes,
+        owned: uid.is_some() && uid == own_uid,
+    }
+}
+
+fn matches_filter(p: &ProcessEntry, filter: &str, user: Option<&str>) -> bool {
+    if user.is_some_and(|u| p.user.as_deref() != Some(u)) {
+        return false;
+    }
+    if filter.is_empty() {
+        return true;
+    }
+    if let Ok(pid) = filter.parse::<u32>() {
+        return p.pid == pid;
+    }
+    let filter = filter.to_lowercase();
+    p.name.to_lowercase().contains(&filter) || p.cmdline.to_lowercase().contains(&filter)
+}
+
+fn sort_processes(list: &mut [ProcessEntry], sort_by: &str, descending: bool) -> Result<(), String> {
+    let key: fn(&ProcessEntry, &ProcessEntry) -> std::cmp::Ordering = match sort_by {
+        "pid" => |a, b| a.pid.cmp(&b.pid),
+        "name" => |a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        "user" => |a, b| a.user.cmp(&b.user),
+        "cpu" => |a, b| a.cpu_usage_percent.total_cmp(&b.cpu_usage_percent),
+        "memory" => |a, b| a.memory_rss_bytes.cmp(&b.memory_rss_bytes),
+        "start_time" => |a, b| a.start_time_s.cmp(&b.start_time_s),
+        "disk_read" => |a, b| a.disk_read_bytes_per_s.total_cmp(&b.disk_read_bytes_per_s),
+        "disk_write" => |a, b| a.disk_write_bytes_per_s.total_cmp(&b.disk_write_bytes_per_s),
+        other => return Err(format!("Unknown sort key '{}'", other)),
+    };
+    list.sort_by(|a, b| if descending { key(b, a) } else { key(a, b) });
+    Ok(())
+}
+
+/// Builds the forest from already sorted entries, so siblings keep that order.
+/// Processes whose parent is not in the list become roots.
+fn build_tree(sorted: Vec<ProcessEntry>) -> Vec<ProcessTreeNode> {
+    let pids: HashSet<u32> = sorted.iter().map(|p| p.pid).collect();
+    let mut children: HashMap<u32, Vec<ProcessEntry>> = HashMap::new();
+    let mut roots = Vec::new();
+    for p in sorted {
+        match p.ppid.filter(|ppid| pids.contains(ppid) && *ppid != p.pid) {
+            Some(ppid) => children.entry(ppid).or_default().push(p),
+            None => roots.push(p),
+        }
+    }
+
+    fn attach(p: ProcessEntry, children: &mut HashMap<u32, Vec<ProcessEntry>>) -> ProcessTreeNode {
+        let kids = children.remove(&p.pid).unwrap_or_default();
+        ProcessTreeNode { children: kids.into_iter().map(|c| attach(c, children)).collect(), process: p }
+    }
+    roots.into_iter().map(|p| attach(p, &mut children)).collect()
+}
+
+// -----------------------------------------------------------------------------
+// Actions
+// -----------------------------------------------------------------------------
+fn process_uid(pid: u32) -> Result<u32, String> {
+    fs::read_to_string(format!("/proc/{}/status", pid))
+        .map_err(|_| format!("Process {} does not exist.", pid))?
+        .lines()
+        .find_map(|l| l.strip_prefix("Uid:"))
+        .and_then(|l| l.split_whitespace().next())
+        .and_then(|u| u.parse().ok())
+        .ok_or_else(|| format!("Cannot read the owner of process {}.", pid))
+}
+
+/// Runs the tool directly for own processes and through pkexec otherwise.
+fn run_on_process(pid: u32, needs_root: bool, program: &str, args: &[&str]) -> Result<ProcessActionResult, String> {
+    if pid <= 1 {
+        return Err("Refusing to act on PID 0 or 1.".into());
+    }
+    let elevated = needs_root || current_uid() != Some(process_uid(pid)?);
+
+    if elevated {
+        run_as_root(program, args)?;
+    } else {
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to execute {}: {}", program, e))?;
+        if !output.status.success() {
+            return Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()));
+        }
+    }
+
+    Ok(ProcessActionResult {
+        success: true,
+        message: format!("{} {} applied to process {}.", program, args.join(" "), pid),
+        pid,
+        elevated,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Tauri commands
+// -----------------------------------------------------------------------------
+
+/// `sort_by`: pid, name, user, cpu, memory, start_time, disk_read or disk_write (default cpu,
+/// descending). `filter` matches a PID exactly or name/command line case-insensitively.
+/// Refreshing every process reads all of /proc, so it runs on a blocking thread.
+#[tauri::command]
+pub async fn list_processes(
+    manager: State<'_, ProcessManager>,
+    sort_by: Option<String>,
+    descending: Option<bool>,
+    filter: Option<String>,
+    user: Option<String>,
+    tree: Option<bool>,
+) -> Result<ProcessList, String> {
+    let state = manager.state.clone();
+    tokio::task::spawn_blocking(move || collect_processes(&state, sort_by, descending, filter, user, tree))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn collect_processes(
+    state: &Mutex<ProcessState>,
+    sort_by: Option<String>,
+    descending: Option<bool>,
+    filter: Option<String>,
+    user: Option<String>,
+    tree: Option<bool>,
+) -> Result<ProcessList, String> {
+    let mut state = state.lock().unwrap();
+    let (sys, users, last_refresh) = &mut *state;
+
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .with_cpu()
+            .with_memory()
+            .with_disk_usage()
+            .with_user(sysinfo::UpdateKind::OnlyIfNotSet)
+            .with_cmd(sysinfo::UpdateKind::OnlyIfNotSet)
+            .with_exe(sysinfo::UpdateKind::OnlyIfNotSet),
+    );
+    users.refresh();
+    let elapsed_s = last_refresh.map(|t| t.elapsed().as_secs_f64());
+    *last_refresh = Some(Instant::now());
+
+    let own_uid = current_uid();
+    // Threads show up as processes in sysinfo; the manager lists processes only.
+    let all: Vec<ProcessEntry> = sys
+        .processes()
+        .values()
+        .filter(|p| p.thread_kind().is_none())
+        .map(|p| entry(p, users, own_uid, elapsed_s))
+        .collect();
+    let total = all.len();
+
+    let filter = filter.unwrap_or_default();
+    let want_tree = tree.unwrap_or(false);
+    let mut selected: Vec<ProcessEntry> = if want_tree && (!filter.is_empty() || user.is_some()) {
+        // Keep ancestors of matches so they stay reachable from a root.
+        let by_pid: HashMap<u32, &ProcessEntry> = all.iter().map(|p| (p.pid, p)).collect();
+        let mut keep = HashSet::new();
+        for p in all.iter().filter(|p| matches_filter(p, &filter, user.as_deref())) {
+            let mut current = Some(p);
+            while let Some(c) = current {
+                if !keep.insert(c.pid) {
+                    break;
+                }
+                current = c.ppid.and_then(|pp| by_pid.get(&pp).copied());
+            }
+        }
+        all.iter().filter(|p| keep.contains(&p.pid)).cloned().collect()
+    } else {
+        all.into_iter().filter(|p| matches_filter(p, &filter, user.as_deref())).collect()
+    };
+
+    sort_processes(&mut selected, sort_by.as_deref().unwrap_or("cpu"), descending.unwrap_or(true))?;
+
+    Ok(if want_tree {
+        ProcessList { total, processes: Vec::new(), tree: build_tree(selected) }
+    } else {
+        ProcessList { total, processes: selected, tree: Vec::new() }
+    })
+}
+
+/// `signal` is a name without the SIG prefix: TERM, KILL, HUP, INT, QUIT, STOP, CONT, USR1, USR2.
+#[tauri::command]
+pub async fn send_process_signal(pid: u32, signal: String) -> Result<ProcessActionResult, String> {
+    let signal = signal.trim_start_matches("SIG").to_uppercase();
+    if !SIGNALS.contains(&signal.as_str()) {
+        return Err(format!("Unsupported signal '{}', expected one of: {}", signal, SIGNALS.join(", ")));
+    }
+    let pid_str = pid.to_string();
+    run_blocking(pid, false, "kill", vec!["-s".into(), signal, pid_str]).await
+}
+
+/// Lowering the nice value below the current one always needs root.
+#[tauri::command]
+pub async fn renice_process(pid: u32, nice: i32) -> Result<ProcessActionResult, String> {
+    if !(-20..=19).contains(&nice) {
+        return Err(format!("Nice value {} is out of range (-20 to 19).", nice));
+    }
+    let raising_priority = read_nice(pid).is_none_or(|current| nice < current);
+    let args = vec!["-n".into(), nice.to_string(), "-p".into(), pid.to_string()];
+    run_blocking(pid, raising_priority, "renice", args).await
+}
+
+/// `class`: "realtime" (root only), "best-effort" or "idle"; `level` 0-7 (highest to lowest)
+/// applies to the first two.
+#[tauri::command]
+pub async fn set_process_io_priority(pid: u32, class: String, level: Option<u8>) -> Result<ProcessActionResult, String> {
+    let class_id = match class.as_str() {
+        "realtime" => "1",
+        "best-effort" => "2",
+        "idle" => "3",
+        other => return Err(format!("Unknown I/O class '{}', expected realtime, best-effort or idle.", other)),
+    };
+    let mut args = vec!["-c".to_string(), class_id.to_string()];
+    if class_id != "3" {
+        let level = level.unwrap_or(4);
+        if level > 7 {
+            return Err(format!("I/O priority level {} is out of range (0 to 7).", level));
+        }
+        args.extend(["-n".to_string(), level.to_string()]);
+    }
+    args.extend(["-p".to_string(), pid.to_string()]);
+    run_blocking(pid, class_id == "1", "ionice", args).await
+}
+
+/// `run_on_process` on a blocking thread: the pkexec fallback waits for the user.
+async fn run_blocking(
+    pid: u32,
+    needs_root: bool,
+    program: &'static str,
+    args: Vec<String>,
+) -> Result<ProcessActionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        run_on_process(pid, needs_root, program, &args)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -1,7 +1,153 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use std::fs;
 use std::path::Path;
+use std::time::UNIX_EPOCH;
+use sysinfo::System;
 
-/// Arch (systemd) marks a reboot as needed by creating `/var/run/reboot-required`
-pub fn is_reboot_required() -> bool {
-    Path::new("/var/run/reboot-required").exists()
+use crate::model::RebootReason;
+
+const PACMAN_LOG_PATH: &str = "/var/log/pacman.log";
+const MODULES_PATH: &str = "/usr/lib/modules";
+const LOCALE_CONF_PATH: &str = "/etc/locale.conf";
+/// Debian convention; Arch never creates it, but some tools do.
+const FLAG_FILE_PATH: &str = "/var/run/reboot-required";
+
+/// Packages that are only picked up by a fresh boot, besides the running kernel.
+const BOOT_PACKAGES: &[&str] = &["systemd", "systemd-libs", "glibc", "intel-ucode", "amd-ucode"];
+/// Used when the running kernel's pkgbase cannot be read (its modules are gone).
+const KERNEL_PACKAGES: &[&str] = &["linux", "linux-lts", "linux-zen", "linux-hardened", "linux-rt", "linux-rt-lts"];
+
+/// Every reason found, one entry per package or condition.
+pub fn reboot_reasons() -> Vec<RebootReason> {
+    let boot_time = Utc.timestamp_opt(System::boot_time() as i64, 0).single().unwrap_or_else(Utc::now);
+    let release = System::kernel_version().unwrap_or_default();
+    let modules_dir = Path::new(MODULES_PATH).join(&release);
+
+    let mut reasons = Vec::new();
+
+    if !release.is_empty() && !modules_dir.exists() {
+        reasons.push(RebootReason {
+            kind: "kernel-modules-missing".into(),
+            message: format!(
+                "The modules of the running kernel ({}) were removed; new hardware and modules cannot be loaded.",
+                release
+            ),
+            package: None,
+            changed_at: None,
+        });
+    }
+
+    let kernel_pkgbase = fs::read_to_string(modules_dir.join("pkgbase")).ok().map(|s| s.trim().to_string());
+    reasons.extend(upgraded_since(boot_time, kernel_pkgbase.as_deref()));
+    reasons.extend(locale_changed_since(boot_time));
+
+    if Path::new(FLAG_FILE_PATH).exists() {
+        reasons.push(RebootReason {
+            kind: "flag-file".into(),
+            message: format!("{} exists.", FLAG_FILE_PATH),
+            package: None,
+            changed_at: None,
+        });
+    }
+
+    reasons
+}
+
+// -----------------------------------------------------------------------------
+// pacman.log
+// -----------------------------------------------------------------------------
+
+/// "[2024-05-01T12:34:56+0200] ..." or the pre-5.1 "[2019-01-01 12:34] ..." in local time.
+fn log_timestamp(line: &str) -> Option<DateTime<Utc>> {
+    let raw = line.strip_prefix('[')?.split(']').next()?;
+    if let Ok(dt) = DateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%#z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+    let naive = NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M").ok()?;
+    Local.from_local_datetime(&naive).single().map(|dt| dt.with_timezone(&Utc))
+}
+
+/// "[...] [ALPM] upgraded linux (6.8.1.arch1-1 -> 6.8.2.arch1-1)" → ("linux", "6.8.2.arch1-1")
+fn parse_alpm_change(line: &str) -> Option<(&str, &str)> {
+    let rest = line.split("[ALPM] ").nth(1)?;
+    let rest = ["upgraded ", "installed ", "reinstalled ", "downgraded "]
+        .iter()
+        .find_map(|verb| rest.strip_prefix(verb))?;
+    let (name, versions) = rest.split_once(' ')?;
+    let versions = versions.trim().trim_start_matches('(').trim_end_matches(')');
+    let version = versions.rsplit(" -> ").next()?;
+    Some((name, version))
+}
+
+/// The latest change per relevant package since boot, newest first. The log is
+/// read backwards so only the entries after boot are parsed.
+fn upgraded_since(boot_time: DateTime<Utc>, kernel_pkgbase: Option<&str>) -> Vec<RebootReason> {
+    let Ok(log) = fs::read_to_string(PACMAN_LOG_PATH) else {
+        return Vec::new();
+    };
+
+    let is_relevant = |name: &str| match kernel_pkgbase {
+        Some(pkgbase) => name == pkgbase || BOOT_PACKAGES.contains(&name),
+        None => KERNEL_PACKAGES.contains(&name) || BOOT_PACKAGES.contains(&name),
+    };
+
+    let mut reasons: Vec<RebootReason> = Vec::new();
+    for line in log.lines().rev() {
+        let Some(at) = log_timestamp(line) else { continue };
+        if at < boot_time {
+            break;
+        }
+        let Some((name, version)) = parse_alpm_change(line) else { continue };
+        if !is_relevant(name) || reasons.iter().any(|r| r.package.as_deref() == Some(name)) {
+            continue;
+        }
+
+        let what = if BOOT_PACKAGES.contains(&name) { "" } else { "kernel " };
+        reasons.push(RebootReason {
+            kind: "package-upgraded".into(),
+            message: format!("{}{} was updated to {} after boot.", what, name, version),
+            package: Some(name.to_string()),
+            changed_at: Some(at.to_rfc3339()),
+        });
+    }
+    reasons
 }
 
+// -----------------------------------------------------------------------------
+// Locale
+// -----------------------------------------------------------------------------
+
+/// /etc/locale.conf was written after boot and no longer matches what this session runs with.
+fn locale_changed_since(boot_time: DateTime<Utc>) -> Option<RebootReason> {
+    let modified = fs::metadata(LOCALE_CONF_PATH).and_then(|m| m.modified()).ok()?;
+    let modified = modified.duration_since(UNIX_EPOCH).ok()?;
+    let modified = Utc.timestamp_opt(modified.as_secs() as i64, 0).single()?;
+    if modified < boot_time {
+        return None;
+    }
+
+    let content = fs::read_to_string(LOCALE_CONF_PATH).ok()?;
+    let configured = content.lines().find_map(|line| {
+        let v = line.trim().strip_prefix("LANG=")?;
+        Some(v.trim_matches('"').to_string())
+    })?;
+    let session = std::env::var("LANG").unwrap_or_default();
+    if !session.is_empty() && session == configured {
+        return None;
+    }
+
+    Some(RebootReason {
+        kind: "locale-changed".into(),
+        message: format!("The system locale was changed to {} after boot.", configured),
+        package: None,
+        changed_at: Some(modified.to_rfc3339()),
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Tauri command
+// -----------------------------------------------------------------------------
+#[tauri::command]
+pub async fn get_reboot_reasons() -> Result<Vec<RebootReason>, String> {
+    Ok(reboot_reasons())
+}
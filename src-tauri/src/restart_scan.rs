@@ -0,0 +1,305 @@
+// src/restart_scan.rs
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+use crate::pacman_manager::{emit_progress, run_command_with_status};
+use crate::privileged::run_as_root;
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+/// Only replaced code counts; deleted files under /tmp, /dev/shm, memfd: etc. are normal.
+const CODE_PREFIXES: &[&str] = &["/usr/", "/lib", "/opt/", "/bin/", "/sbin/"];
+/// Restarting these ends the graphical session or breaks the system bus; they need a reboot.
+const CRITICAL_UNITS: &[&str] = &[
+    "dbus.service", "dbus-broker.service", "systemd-logind.service", "display-manager.service",
+    "gdm.service", "sddm.service", "lightdm.service", "greetd.service", "ly.service",
+    "lxdm.service", "polkit.service",
+];
+const OP_DESC: &str = "Service Restart";
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Clone)]
+pub struct StaleProcess {
+    pub pid: u32,
+    pub name: String,
+    pub uid: Option<u32>,
+    /// The systemd unit the process runs in, from its cgroup.
+    pub unit: Option<String>,
+    /// True for units of a user manager (`user@1000.service/...`).
+    pub user_unit: bool,
+    pub deleted_files: Vec<String>,
+    pub packages: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServiceToRestart {
+    pub unit: String,
+    pub pids: Vec<u32>,
+    pub packages: Vec<String>,
+    /// Restarting it would end the session; a reboot is the safe option.
+    pub critical: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestartScanReport {
+    pub services: Vec<ServiceToRestart>,
+    /// Processes outside system services (sessions, user units): log out or restart them by hand.
+    pub processes: Vec<StaleProcess>,
+    /// PID 1 itself maps deleted files; `systemctl daemon-reexec` fixes that.
+    pub systemd_reexec_needed: bool,
+    /// False when only the caller's own processes could be read.
+    pub complete: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServiceRestartResult {
+    pub success: bool,
+    pub message: String,
+    pub restarted: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+// -----------------------------------------------------------------------------
+// Scanning /proc
+// -----------------------------------------------------------------------------
+
+/// "7f1c2a000000-7f1c2a022000 r-xp 00000000 00:1f 1234  /usr/lib/libssl.so.3 (deleted)"
+/// The path is everything after the fifth field and may itself contain spaces.
+fn deleted_mapping(line: &str) -> Option<&str> {
+    let path = line.strip_suffix(" (deleted)")?.splitn(6, ' ').nth(5)?.trim_start();
+    CODE_PREFIXES.iter().any(|p| path.starts_with(p)).then_some(path)
+}
+
+/// Deleted mappings per pid from the maps files we can read ourselves.
+fn scan_own() -> BTreeMap<u32, Vec<String>> {
+    let mut found: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    let Ok(entries) = fs::read_dir("/proc") else { return found };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else { continue };
+        let Ok(maps) = fs::read_to_string(entry.path().join("maps")) else { continue };
+        for path in maps.lines().filter_map(deleted_mapping) {
+            let files = found.entry(pid).or_default();
+            if !files.iter().any(|f| f == path) {
+                files.push(path.to_string());
+            }
+        }
+    }
+    found
+}
+
+/// The same through a single pkexec call, covering every process.
+fn scan_elevated() -> Result<BTreeMap<u32, Vec<String>>, String> {
+    // grep exits 1 when nothing matches, which is the good case.
+    let output = run_as_root("sh", &["-c", "grep -H ' (deleted)$' /proc/[0-9]*/maps; true"])?;
+
+    let mut found: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    for line in output.lines() {
+        // "/proc/1234/maps:7f1c... /usr/lib/libssl.so.3 (deleted)"
+        let Some((file, mapping)) = line.split_once(':') else { continue };
+        let Some(pid) = file.strip_prefix("/proc/").and_then(|r| r.strip_suffix("/maps")) else { continue };
+        let (Ok(pid), Some(path)) = (pid.parse::<u32>(), deleted_mapping(mapping)) else { continue };
+        let files = found.entry(pid).or_default();
+        if !files.iter().any(|f| f == path) {
+            files.push(path.to_string());
+        }
+    }
+    Ok(found)
+}
+
+/// "0::/system.slice/sshd.service" → ("sshd.service", false);
+/// "0::/user.slice/user-1000.slice/user@1000.service/app.slice/foo.service" → ("foo.service", true).
+/// Session scopes are not restartable and yield None.
+fn unit_of(pid: u32) -> Option<(String, bool)> {
+    let cgroup = fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    let path = cgroup.lines().find_map(|l| l.strip_prefix("0::"))?;
+    let user_unit = path.contains("/user@");
+    let unit = path.rsplit('/').find(|part| part.ends_with(".service"))?;
+    if unit.starts_with("user@") {
+        return None;
+    }
+    Some((unit.to_string(), user_unit))
+}
+
+fn process_uid(pid: u32) -> Option<u32> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    status
+        .lines()
+        .find_map(|l| l.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Most replaced files exist again under the same path, so pacman can still name
+/// the owner. One `pacman -Qo` call for all of them; paths nobody owns (or that are
+/// gone) only produce errors on stderr, so the exit code is ignored.
+async fn package_owners(paths: &[&String]) -> HashMap<String, String> {
+    let existing: Vec<&str> = paths.iter().map(|p| p.as_str()).filter(|p| Path::new(p).exists()).collect();
+    if existing.is_empty() {
+        return HashMap::new();
+    }
+    let Ok(output) = tokio::process::Command::new("pacman")
+        .arg("-Qo")
+        .args(&existing)
+        .env("LC_ALL", "C")
+        .output()
+        .await
+    else {
+        return HashMap::new();
+    };
+
+    // "/usr/lib/libssl.so.3 is owned by openssl 3.3.1-1"
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (path, owner) = line.rsplit_once(" is owned by ")?;
+            Some((path.to_string(), owner.split_whitespace().next()?.to_string()))
+        })
+        .collect()
+}
+
+async fn scan(elevated: bool) -> Result<RestartScanReport, String> {
+    let found = if elevated {
+        tokio::task::spawn_blocking(scan_elevated).await.map_err(|e| e.to_string())??
+    } else {
+        scan_own()
+    };
+
+    let mut paths: Vec<&String> = found.values().flatten().collect();
+    paths.sort();
+    paths.dedup();
+    let owners = package_owners(&paths).await;
+
+    let mut systemd_reexec_needed = false;
+    let mut services: BTreeMap<String, ServiceToRestart> = BTreeMap::new();
+    let mut processes = Vec::new();
+
+    for (pid, deleted_files) in &found {
+        if *pid == 1 {
+            systemd_reexec_needed = true;
+            continue;
+        }
+
+        let mut packages: Vec<String> = deleted_files.iter().filter_map(|f| owners.get(f).cloned()).collect();
+        packages.sort();
+        packages.dedup();
+        let unit = unit_of(*pid);
+
+        match &unit {
+            Some((name, false)) => {
+                let service = services.entry(name.clone()).or_insert_with(|| ServiceToRestart {
+                    unit: name.clone(),
+                    pids: Vec::new(),
+                    packages: Vec::new(),
+                    critical: CRITICAL_UNITS.contains(&name.as_str()),
+                });
+                service.pids.push(*pid);
+                for p in packages {
+                    if !service.packages.contains(&p) {
+                        service.packages.push(p);
+                    }
+                }
+            }
+            _ => processes.push(StaleProcess {
+                pid: *pid,
+                name: fs::read_to_string(format!("/proc/{}/comm", pid)).map(|s| s.trim().to_string()).unwrap_or_default(),
+                uid: process_uid(*pid),
+                user_unit: unit.as_ref().is_some_and(|(_, user)| *user),
+                unit: unit.map(|(name, _)| name),
+                deleted_files: deleted_files.clone(),
+                packages,
+            }),
+        }
+    }
+
+    Ok(RestartScanReport {
+        services: services.into_values().collect(),
+        processes,
+        systemd_reexec_needed,
+        complete: elevated,
+    })
+}
+
+fn valid_unit(unit: &str) -> bool {
+    unit.ends_with(".service")
+        && !unit.starts_with('-')
+        && unit.chars().all(|c| c.is_ascii_alphanumeric() || "@._-:\\".contains(c))
+}
+
+// -----------------------------------------------------------------------------
+// Tauri commands
+// -----------------------------------------------------------------------------
+
+/// Without `elevated` only the caller's own processes are readable, which misses
+/// every system service; with it the scan runs once through pkexec.
+#[tauri::command]
+pub async fn scan_processes_needing_restart(elevated: Option<bool>) -> Result<RestartScanReport, String> {
+    scan(elevated.unwrap_or(false)).await
+}
+
+/// Restarts the given system units; `systemd` re-executes PID 1 instead.
+#[tauri::command]
+pub async fn restart_services(app_handle: AppHandle, units: Vec<String>) -> Result<ServiceRestartResult, String> {
+    let reexec = units.iter().any(|u| u == "systemd");
+    let units: Vec<String> = units.into_iter().filter(|u| u != "systemd").collect();
+
+    if let Some(bad) = units.iter().find(|u| !valid_unit(u)) {
+        return Err(format!("'{}' is not a service unit.", bad));
+    }
+    if let Some(critical) = units.iter().find(|u| CRITICAL_UNITS.contains(&u.as_str())) {
+        return Err(format!("Restarting {} would end the session, reboot instead.", critical));
+    }
+    if units.is_empty() && !reexec {
+        return Err("No services selected.".into());
+    }
+
+    // One pkexec prompt for the whole batch; each unit is restarted separately so one
+    // failure does not hide the others.
+    let mut script = String::from("failed=''; ");
+    if reexec {
+        script.push_str("systemctl daemon-reexec || failed=\"$failed systemd\"; ");
+    }
+    script.push_str("for u in \"$@\"; do systemctl restart \"$u\" || failed=\"$failed $u\"; done; echo \"FAILED:$failed\"");
+
+    let mut args = vec!["sh", "-c", script.as_str(), "sh"];
+    args.extend(units.iter().map(String::as_str));
+
+    emit_progress(&app_handle, OP_DESC, &format!("Restarting {} service(s)...", units.len() + reexec as usize));
+    let output = run_command_with_status("pkexec", &args, &app_handle, OP_DESC).await?;
+    if matches!(output.exit_code, Some(126) | Some(127)) {
+        return Err(format!(
+            "Root permission denied or cancelled by user. (Exit Code: {})",
+            output.exit_code.unwrap_or(-1)
+        ));
+    }
+
+    let failed: Vec<String> = output
+        .stdout
+        .lines()
+        .find_map(|l| l.strip_prefix("FAILED:"))
+        .map(|f| f.split_whitespace().map(|s| s.to_string()).collect())
+        .unwrap_or_default();
+    let restarted: Vec<String> = units
+        .into_iter()
+        .chain(reexec.then(|| "systemd".to_string()))
+        .filter(|u| !failed.contains(u))
+        .collect();
+
+    let success = failed.is_empty();
+    let message = if success {
+        format!("Restarted {} service(s).", restarted.len())
+    } else {
+        format!("Failed to restart: {}", failed.join(", "))
+    };
+    emit_progress(&app_handle, OP_DESC, &message);
+
+    Ok(ServiceRestartResult { success, message, restarted, failed })
+}
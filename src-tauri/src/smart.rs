@@ -0,0 +1,435 @@
+// src/smart.rs
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State, Wry};
+
+use crate::privileged::run_as_root;
+use crate::storage::block_devices;
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+pub const DRIVE_FAILING_EVENT: &str = "drive-failing";
+const SEPARATOR: &str = "@@LINUXHUB-SMART@@";
+/// pkexec runs with a fixed PATH; smartmontools installs to one of these.
+const SMARTCTL_PATHS: &[&str] = &["/usr/bin/smartctl", "/usr/sbin/smartctl"];
+/// NVMe "percentage used" and ATA wear indicators at or above this are worth a warning.
+const WEAR_WARNING_PERCENT: u8 = 90;
+/// smartctl exit status bits (see smartctl(8) "RETURN VALUES").
+const EXIT_OPEN_FAILED: i64 = 1 << 1;
+const EXIT_DISK_FAILING: i64 = 1 << 3;
+const EXIT_PREFAIL_NOW: i64 = 1 << 4;
+
+// -----------------------------------------------------------------------------
+// smartctl --json output (only the fields we use)
+// -----------------------------------------------------------------------------
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct SmartctlOutput {
+    smartctl: SmartctlMeta,
+    device: SmartctlDevice,
+    model_name: Option<String>,
+    serial_number: Option<String>,
+    firmware_version: Option<String>,
+    user_capacity: Option<Capacity>,
+    smart_status: Option<SmartStatus>,
+    power_on_time: Option<PowerOnTime>,
+    power_cycle_count: Option<u64>,
+    temperature: Option<Temperature>,
+    ata_smart_attributes: Option<AtaAttributes>,
+    ata_smart_error_log: Option<AtaErrorLog>,
+    nvme_smart_health_information_log: Option<NvmeHealthLog>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct SmartctlMeta {
+    exit_status: i64,
+    messages: Vec<SmartctlMessage>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct SmartctlMessage {
+    string: String,
+    severity: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct SmartctlDevice {
+    name: String,
+    protocol: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Capacity {
+    bytes: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SmartStatus {
+    passed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PowerOnTime {
+    hours: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Temperature {
+    current: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AtaAttributes {
+    table: Vec<AtaAttribute>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AtaAttribute {
+    id: u32,
+    name: String,
+    value: u32,
+    worst: u32,
+    thresh: u32,
+    #[serde(default)]
+    when_failed: String,
+    raw: AtaRaw,
+}
+
+#[derive(Debug, Deserialize)]
+struct AtaRaw {
+    value: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AtaErrorLog {
+    summary: Option<AtaErrorSummary>,
+    extended: Option<AtaErrorSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AtaErrorSummary {
+    count: u64,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct NvmeHealthLog {
+    critical_warning: u8,
+    available_spare: u8,
+    available_spare_threshold: u8,
+    percentage_used: u8,
+    media_errors: u64,
+    num_err_log_entries: u64,
+    unsafe_shutdowns: Option<u64>,
+}
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Clone)]
+pub struct SmartAttribute {
+    pub id: u32,
+    pub name: String,
+    pub value: u32,
+    pub worst: u32,
+    pub threshold: u32,
+    pub raw: u64,
+    /// "now", "past" or empty.
+    pub when_failed: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct DriveHealth {
+    pub device: String,
+    /// "ATA", "NVMe" or "SCSI".
+    pub protocol: String,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    pub firmware: Option<String>,
+    pub capacity_bytes: Option<u64>,
+    /// SMART overall-health self-assessment.
+    pub smart_passed: Option<bool>,
+    pub power_on_hours: Option<u64>,
+    pub power_cycles: Option<u64>,
+    pub temperature_c: Option<i64>,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    pub uncorrectable_sectors: Option<u64>,
+    /// Share of rated endurance used (NVMe "percentage used", ATA wear attributes).
+    pub percentage_used: Option<u8>,
+    pub available_spare: Option<u8>,
+    pub media_errors: Option<u64>,
+    pub unsafe_shutdowns: Option<u64>,
+    pub error_log_count: Option<u64>,
+    /// NVMe critical warning bit field; 0 is healthy.
+    pub critical_warning: Option<u8>,
+    pub attributes: Vec<SmartAttribute>,
+    pub failing: bool,
+    /// Human-readable reasons behind `failing`, plus early warnings.
+    pub warnings: Vec<String>,
+    /// Set when smartctl could not read the drive; the other fields are then empty.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DriveFailingEvent {
+    pub device: String,
+    pub model: Option<String>,
+    pub reasons: Vec<String>,
+}
+
+// -----------------------------------------------------------------------------
+// Parsing
+// -----------------------------------------------------------------------------
+fn attribute_raw(attributes: &[SmartAttribute], ids: &[u32]) -> Option<u64> {
+    attributes.iter().find(|a| ids.contains(&a.id)).map(|a| a.raw)
+}
+
+/// Vendors report SSD life left as a normalized value counting down from 100:
+/// 177 Wear_Leveling_Count (Samsung), 231 SSD_Life_Left, 233 Media_Wearout_Indicator (Intel).
+fn ata_percentage_used(attributes: &[SmartAttribute]) -> Option<u8> {
+    let remaining = attributes.iter().find(|a| [177, 231, 233].contains(&a.id))?.value;
+    Some(100u32.saturating_sub(remaining.min(100)) as u8)
+}
+
+fn parse_health(device: &str, raw: &str) -> DriveHealth {
+    let parsed: Result<SmartctlOutput, _> = serde_json::from_str(raw);
+    let output = match parsed {
+        Ok(output) => output,
+        Err(e) => return unreadable(device, format!("Unexpected smartctl output: {}", e)),
+    };
+    let exit = output.smartctl.exit_status;
+    if exit & EXIT_OPEN_FAILED != 0 {
+        let reason = output
+            .smartctl
+            .messages
+            .iter()
+            .find(|m| m.severity == "error")
+            .map(|m| m.string.clone())
+            .unwrap_or_else(|| "smartctl could not open the device.".into());
+        return unreadable(device, reason);
+    }
+
+    let attributes: Vec<SmartAttribute> = output
+        .ata_smart_attributes
+        .map(|a| a.table)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|a| SmartAttribute {
+            id: a.id,
+            name: a.name,
+            value: a.value,
+            worst: a.worst,
+            threshold: a.thresh,
+            raw: a.raw.value,
+            when_failed: a.when_failed,
+        })
+        .collect();
+    let nvme = output.nvme_smart_health_information_log;
+
+    let mut health = DriveHealth {
+        device: if output.device.name.is_empty() { device.to_string() } else { output.device.name },
+        protocol: output.device.protocol,
+        model: output.model_name,
+        serial: output.serial_number,
+        firmware: output.firmware_version,
+        capacity_bytes: output.user_capacity.map(|c| c.bytes),
+        smart_passed: output.smart_status.map(|s| s.passed),
+        power_on_hours: output.power_on_time.map(|p| p.hours),
+        power_cycles: output.power_cycle_count,
+        temperature_c: output.temperature.and_then(|t| t.current),
+        reallocated_sectors: attribute_raw(&attributes, &[5]),
+        pending_sectors: attribute_raw(&attributes, &[197]),
+        uncorrectable_sectors: attribute_raw(&attributes, &[198]),
+        percentage_used: nvme.as_ref().map(|n| n.percentage_used).or_else(|| ata_percentage_used(&attributes)),
+        available_spare: nvme.as_ref().map(|n| n.available_spare),
+        media_errors: nvme.as_ref().map(|n| n.media_errors),
+        unsafe_shutdowns: nvme.as_ref().and_then(|n| n.unsafe_shutdowns),
+        error_log_count: nvme.as_ref().map(|n| n.num_err_log_entries).or_else(|| {
+            let log = output.ata_smart_error_log.as_ref()?;
+            log.extended.as_ref().or(log.summary.as_ref()).map(|s| s.count)
+        }),
+        critical_warning: nvme.as_ref().map(|n| n.critical_warning),
+        attributes,
+        failing: false,
+        warnings: Vec::new(),
+        error: None,
+    };
+    assess(&mut health, exit, nvme.as_ref());
+    health
+}
+
+fn unreadable(device: &str, error: String) -> DriveHealth {
+    DriveHealth { device: device.to_string(), error: Some(error), ..Default::default() }
+}
+
+/// Failing: the drive or its firmware says so, or it is out of spare blocks/endurance.
+/// Warnings: early signs that usually precede that (remapped or pending sectors, high wear).
+fn assess(health: &mut DriveHealth, exit: i64, nvme: Option<&NvmeHealthLog>) {
+    let mut failing = Vec::new();
+    let mut warnings = Vec::new();
+
+    if health.smart_passed == Some(false) || exit & EXIT_DISK_FAILING != 0 {
+        failing.push("SMART overall-health self-assessment failed.".to_string());
+    }
+    if exit & EXIT_PREFAIL_NOW != 0 {
+        for a in health.attributes.iter().filter(|a| a.when_failed == "now") {
+            failing.push(format!("Attribute {} ({}) is below its threshold.", a.name, a.id));
+        }
+    }
+    if let Some(nvme) = nvme {
+        if nvme.critical_warning != 0 {
+            failing.push(format!("NVMe critical warning 0x{:02x}.", nvme.critical_warning));
+        }
+        if nvme.available_spare < nvme.available_spare_threshold {
+            failing.push(format!(
+                "Available spare {}% is below the {}% threshold.",
+                nvme.available_spare, nvme.available_spare_threshold
+            ));
+        }
+        if nvme.media_errors > 0 {
+            warnings.push(format!("{} media/data integrity error(s).", nvme.media_errors));
+        }
+    }
+    match health.percentage_used {
+        Some(used) if used >= 100 => failing.push("Rated endurance is used up.".to_string()),
+        Some(used) if used >= WEAR_WARNING_PERCENT => warnings.push(format!("{}% of rated endurance used.", used)),
+        _ => {}
+    }
+    for (count, what) in [
+        (health.reallocated_sectors, "reallocated"),
+        (health.pending_sectors, "pending"),
+        (health.uncorrectable_sectors, "uncorrectable"),
+    ] {
+        if let Some(n) = count.filter(|n| *n > 0) {
+            warnings.push(format!("{} {} sector(s).", n, what));
+        }
+    }
+
+    health.failing = !failing.is_empty();
+    failing.extend(warnings);
+    health.warnings = failing;
+}
+
+// -----------------------------------------------------------------------------
+// Running smartctl
+// -----------------------------------------------------------------------------
+fn valid_device(device: &str) -> bool {
+    device.starts_with("/dev/") && !device.contains("..") && !device.contains(char::is_whitespace)
+}
+
+/// Physical disks from sysfs; partitions and virtual devices have no SMART data.
+fn default_devices() -> Vec<String> {
+    block_devices()
+        .into_iter()
+        .filter(|d| d.kind == "disk" && d.transport.as_deref().is_some_and(|t| t != "virtio"))
+        .map(|d| d.path)
+        .collect()
+}
+
+/// Works when we already have access to the device nodes (root, or the "disk" group).
+fn read_direct(devices: &[String]) -> Result<Vec<(String, String)>, String> {
+    devices
+        .iter()
+        .map(|device| {
+            let output = Command::new("smartctl")
+                .args(["--json=c", "-a", device])
+                .output()
+                .map_err(|e| format!("Failed to run smartctl (is smartmontools installed?): {}", e))?;
+            Ok((device.clone(), String::from_utf8_lossy(&output.stdout).into_owned()))
+        })
+        .collect()
+}
+
+/// One pkexec prompt for every drive.
+fn read_elevated(devices: &[String]) -> Result<Vec<(String, String)>, String> {
+    // Checked before elevating: exit codes from the root shell cannot be told apart
+    // from pkexec's own, and there is no point asking for a password.
+    if !SMARTCTL_PATHS.iter().any(|p| Path::new(p).exists()) {
+        return Err("smartctl is not installed (package smartmontools).".to_string());
+    }
+    let script = format!(
+        "for d in \"$@\"; do smartctl --json=c -a \"$d\"; echo '{}'; done; true",
+        SEPARATOR
+    );
+    let mut args = vec!["-c", script.as_str(), "sh"];
+    args.extend(devices.iter().map(String::as_str));
+    let output = run_as_root("sh", &args)?;
+    Ok(devices
+        .iter()
+        .cloned()
+        .zip(output.split(SEPARATOR).map(|s| s.trim().to_string()))
+        .collect())
+}
+
+// -----------------------------------------------------------------------------
+// Failure transitions
+// -----------------------------------------------------------------------------
+
+/// Last known failing state per drive, keyed by serial number so a drive keeps its
+/// state when its device name changes (falls back to the device path without one).
+#[derive(Default)]
+pub struct DriveHealthTracker {
+    failing: Mutex<HashMap<String, bool>>,
+}
+
+impl DriveHealthTracker {
+    /// Records the drive's state; true only on a change from healthy (or unseen) to failing.
+    /// Drives smartctl could not read keep their previous state.
+    fn started_failing(&self, drive: &DriveHealth) -> bool {
+        if drive.error.is_some() {
+            return false;
+        }
+        let key = drive.serial.clone().unwrap_or_else(|| drive.device.clone());
+        let mut failing = self.failing.lock().unwrap();
+        let was_failing = failing.insert(key, drive.failing).unwrap_or(false);
+        drive.failing && !was_failing
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tauri command
+// -----------------------------------------------------------------------------
+
+/// Health of the given drives (default: every physical disk). Without `elevated`
+/// smartctl runs as the user, which only works with access to the device nodes;
+/// such drives come back with `error` set. Emits DRIVE_FAILING_EVENT when a drive
+/// starts failing, not again on every refresh while it stays that way.
+#[tauri::command]
+pub async fn get_drive_health(
+    app_handle: AppHandle<Wry>,
+    tracker: State<'_, DriveHealthTracker>,
+    devices: Option<Vec<String>>,
+    elevated: Option<bool>,
+) -> Result<Vec<DriveHealth>, String> {
+    let devices = devices.unwrap_or_else(default_devices);
+    if let Some(bad) = devices.iter().find(|d| !valid_device(d)) {
+        return Err(format!("'{}' is not a device path.", bad));
+    }
+    if devices.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let elevated = elevated.unwrap_or(false);
+    let outputs = tokio::task::spawn_blocking(move || {
+        if elevated { read_elevated(&devices) } else { read_direct(&devices) }
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let report: Vec<DriveHealth> = outputs.iter().map(|(device, raw)| parse_health(device, raw)).collect();
+    for drive in report.iter().filter(|d| tracker.started_failing(d)) {
+        let event = DriveFailingEvent { device: drive.device.clone(), model: drive.model.clone(), reasons: drive.warnings.clone() };
+        if let Err(e) = app_handle.emit(DRIVE_FAILING_EVENT, &event) {
+            eprintln!("Failed to emit drive failing event: {}", e);
+        }
+    }
+    Ok(report)
+}
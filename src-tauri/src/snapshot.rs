@@ -0,0 +1,734 @@
+// src/snapshot.rs
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Manager};
+
+use crate::pacman_manager::emit_progress;
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+const SNAPSHOT_INDEX_FILE: &str = "snapshot-index.json";
+/// Append-only JSON lines log of every elevated snapshot operation.
+const SNAPSHOT_AUDIT_FILE: &str = "snapshot-audit.log";
+const PACMAN_LOCAL_DB: &str = "/var/lib/pacman/local";
+/// Where raw Btrfs snapshots of `/` are created when neither snapper nor Timeshift is set up.
+const BTRFS_SNAPSHOT_DIR: &str = "/.snapshots/linuxhub";
+/// Current and pre-20.x locations of Timeshift's settings.
+const TIMESHIFT_CONFIGS: &[&str] = &["/etc/timeshift/timeshift.json", "/etc/timeshift.json"];
+/// Keep snapper/Timeshift descriptions short, they are shown in one line.
+const MAX_DESCRIPTION_LEN: usize = 120;
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotTool {
+    Snapper,
+    Timeshift,
+    Btrfs,
+}
+
+/// A snapshot taken by linuxhub before a pacman transaction.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotRecord {
+    pub tool: SnapshotTool,
+    /// snapper number, Timeshift snapshot name or Btrfs subvolume path.
+    pub snapshot_id: String,
+    pub description: String,
+    pub operation: String,
+    /// `checkupdates` lines (`pacman -Qu` without a database sync) at the time of the snapshot, e.g. "linux 6.6.1-1 -> 6.6.2-1".
+    pub pending_packages: Vec<String>,
+    pub created_at: String,
+    pub transaction_finished_at: Option<String>,
+    pub transaction_success: Option<bool>,
+}
+
+/// A snapshot as listed by its tool, joined with the linuxhub transaction record.
+#[derive(Debug, Serialize, Clone)]
+pub struct SnapshotEntry {
+    pub tool: SnapshotTool,
+    pub snapshot_id: String,
+    pub date: String,
+    pub description: String,
+    pub size_bytes: Option<u64>,
+    pub transaction: Option<SnapshotRecord>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PackageVersionChange {
+    pub name: String,
+    pub snapshot_version: String,
+    pub current_version: String,
+}
+
+/// Package differences between a snapshot and the running system.
+#[derive(Debug, Serialize)]
+pub struct SnapshotPackageDiff {
+    pub snapshot_id: String,
+    /// Installed now, absent in the snapshot.
+    pub added: Vec<String>,
+    /// Present in the snapshot, no longer installed.
+    pub removed: Vec<String>,
+    pub changed: Vec<PackageVersionChange>,
+}
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    timestamp: String,
+    action: &'a str,
+    tool: SnapshotTool,
+    snapshot_id: &'a str,
+    success: bool,
+    message: &'a str,
+}
+
+// -----------------------------------------------------------------------------
+// Tool detection
+// -----------------------------------------------------------------------------
+/// Picks the first usable tool: a snapper config for `/`, then Timeshift, then plain Btrfs.
+pub fn detect_snapshot_tool() -> Option<SnapshotTool> {
+    if snapper_root_config().is_some() {
+        return Some(SnapshotTool::Snapper);
+    }
+    if Path::new("/usr/bin/timeshift").exists() && timeshift_backup_device().is_some() {
+        return Some(SnapshotTool::Timeshift);
+    }
+    if Path::new("/usr/bin/btrfs").exists() && root_fs_type().as_deref() == Some("btrfs") {
+        return Some(SnapshotTool::Btrfs);
+    }
+    None
+}
+
+/// Name of the snapper config whose subvolume is `/` (usually "root").
+pub fn snapper_root_config() -> Option<String> {
+    if !Path::new("/usr/bin/snapper").exists() {
+        return None;
+    }
+    // "config,subvolume" header followed by "root,/"
+    let output = run_cmd("snapper", &["--csvout", "list-configs"]).ok()?;
+    output.lines().skip(1).find_map(|line| {
+        let (config, subvolume) = line.split_once(',')?;
+        (subvolume.trim() == "/").then(|| config.trim().to_string())
+    })
+}
+
+/// (backup device UUID, Btrfs mode) from Timeshift's settings; None until its setup
+/// wizard has picked a device, as some distributions install Timeshift unconfigured.
+fn timeshift_backup_device() -> Option<(String, bool)> {
+    let config: serde_json::Value = TIMESHIFT_CONFIGS
+        .iter()
+        .find_map(|path| serde_json::from_str(&fs::read_to_string(path).ok()?).ok())?;
+    let uuid = config.get("backup_device_uuid")?.as_str().filter(|uuid| !uuid.is_empty())?;
+    // Timeshift stores its booleans as strings.
+    let btrfs_mode = config.get("btrfs_mode").and_then(|v| v.as_str()) == Some("true");
+    Some((uuid.to_string(), btrfs_mode))
+}
+
+fn root_fs_type() -> Option<String> {
+    run_cmd("findmnt", &["-no", "FSTYPE", "/"])
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+// -----------------------------------------------------------------------------
+// Snapshot creation
+// -----------------------------------------------------------------------------
+/// Snapshots `/` before a pacman transaction and records it in the local index.
+pub async fn create_pre_transaction_snapshot(
+    app_handle: &AppHandle,
+    operation: &str,
+) -> Result<SnapshotRecord, String> {
+    let tool = blocking(|| {
+        detect_snapshot_tool().ok_or_else(|| "No snapshot tool available (snapper, Timeshift or Btrfs root).".to_string())
+    })
+    .await?;
+
+    let pending_packages = pending_upgrades().await;
+    let description = snapshot_description(operation, &pending_packages);
+
+    emit_progress(
+        app_handle,
+        "SNAPSHOT",
+        &format!("Creating {:?} snapshot: {}", tool, description),
+    );
+
+    let snapshot_id = blocking({
+        let description = description.clone();
+        move || create_snapshot(tool, &description)
+    })
+    .await?;
+
+    emit_progress(app_handle, "SNAPSHOT", &format!("Snapshot created: {}", snapshot_id));
+
+    let record = SnapshotRecord {
+        tool,
+        snapshot_id,
+        description,
+        operation: operation.to_string(),
+        pending_packages,
+        created_at: Utc::now().to_rfc3339(),
+        transaction_finished_at: None,
+        transaction_success: None,
+    };
+
+    let mut index = load_index(app_handle);
+    index.push(record.clone());
+    save_index(app_handle, &index)?;
+
+    Ok(record)
+}
+
+/// Stores the outcome of the transaction next to the snapshot taken before it.
+pub fn record_transaction_result(app_handle: &AppHandle, snapshot_id: &str, success: bool) {
+    let mut index = load_index(app_handle);
+    if let Some(record) = index.iter_mut().rev().find(|r| r.snapshot_id == snapshot_id) {
+        record.transaction_finished_at = Some(Utc::now().to_rfc3339());
+        record.transaction_success = Some(success);
+    }
+    if let Err(e) = save_index(app_handle, &index) {
+        eprintln!("Failed to update snapshot index: {}", e);
+    }
+}
+
+fn create_snapshot(tool: SnapshotTool, description: &str) -> Result<String, String> {
+    match tool {
+        SnapshotTool::Snapper => {
+            let config = snapper_root_config().unwrap_or_else(|| "root".to_string());
+            let output = run_cmd(
+                "pkexec",
+                &[
+                    "snapper", "-c", &config, "create",
+                    "--type", "single",
+                    "--cleanup-algorithm", "number",
+                    "--print-number",
+                    "--userdata", "linuxhub=pre-transaction",
+                    "--description", description,
+                ],
+            )?;
+            output
+                .lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty() && l.chars().all(|c| c.is_ascii_digit()))
+                .map(|n| n.to_string())
+                .ok_or_else(|| format!("snapper did not report a snapshot number: {}", output))
+        }
+        SnapshotTool::Timeshift => {
+            let output = run_cmd(
+                "pkexec",
+                &["timeshift", "--create", "--scripted", "--comments", description],
+            )?;
+            // "Tagged snapshot '2024-05-01_10-00-00': ondemand"
+            output
+                .lines()
+                .filter(|l| l.contains("snapshot '"))
+                .find_map(|l| l.split('\'').nth(1))
+                .map(|n| n.to_string())
+                .ok_or_else(|| format!("Timeshift did not report a snapshot name: {}", output))
+        }
+        SnapshotTool::Btrfs => {
+            let path = format!(
+                "{}/{}",
+                BTRFS_SNAPSHOT_DIR,
+                Utc::now().format("%Y-%m-%d_%H-%M-%S")
+            );
+            run_cmd(
+                "pkexec",
+                &[
+                    "sh", "-c",
+                    "mkdir -p \"$1\" && btrfs subvolume snapshot -r / \"$2\"",
+                    "sh", BTRFS_SNAPSHOT_DIR, &path,
+                ],
+            )?;
+            Ok(path)
+        }
+    }
+}
+
+/// The snapshot is taken before `pacman -Syu` refreshes the sync databases, so
+/// `pacman -Qu` would list yesterday's updates. checkupdates (pacman-contrib) syncs a
+/// throwaway copy of the databases and sees what -Syu is about to install.
+async fn pending_upgrades() -> Vec<String> {
+    let output = match tokio::process::Command::new("checkupdates").output().await {
+        // Exit code 2 means "no updates", anything else non-zero is a failed sync.
+        Ok(out) if out.status.success() || out.status.code() == Some(2) => Some(out),
+        _ => tokio::process::Command::new("pacman").arg("-Qu").output().await.ok(),
+    };
+    match output {
+        Some(out) => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn snapshot_description(operation: &str, pending: &[String]) -> String {
+    let mut description = format!("linuxhub pre-{}", operation);
+    if !pending.is_empty() {
+        let names: Vec<&str> = pending
+            .iter()
+            .filter_map(|l| l.split_whitespace().next())
+            .collect();
+        description = format!("{}: {} packages ({})", description, names.len(), names.join(" "));
+    }
+    if description.chars().count() > MAX_DESCRIPTION_LEN {
+        description = description.chars().take(MAX_DESCRIPTION_LEN - 3).collect::<String>() + "...";
+    }
+    description
+}
+
+// -----------------------------------------------------------------------------
+// Listing existing snapshots
+// -----------------------------------------------------------------------------
+fn list_tool_snapshots(tool: SnapshotTool) -> Result<Vec<SnapshotEntry>, String> {
+    match tool {
+        SnapshotTool::Snapper => {
+            let config = snapper_root_config().ok_or("No snapper config for / found.")?;
+            let args = [
+                "snapper", "-c", config.as_str(), "--csvout", "list",
+                "--columns", "number,date,description,used-space",
+            ];
+            // Listing is allowed for non-root users only if the config permits it.
+            let output = run_cmd(args[0], &args[1..]).or_else(|_| run_cmd("pkexec", &args))?;
+            Ok(parse_snapper_list(&output, tool))
+        }
+        SnapshotTool::Timeshift => {
+            let output = run_cmd("pkexec", &["timeshift", "--list", "--scripted"])?;
+            Ok(parse_timeshift_list(&output).entries)
+        }
+        SnapshotTool::Btrfs => {
+            let entries = match fs::read_dir(BTRFS_SNAPSHOT_DIR) {
+                Ok(e) => e,
+                Err(_) => return Ok(Vec::new()),
+            };
+            Ok(entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .map(|e| SnapshotEntry {
+                    tool,
+                    snapshot_id: e.path().to_string_lossy().into_owned(),
+                    date: name_to_date(&e.file_name().to_string_lossy()),
+                    description: String::new(),
+                    size_bytes: None,
+                    transaction: None,
+                })
+                .collect())
+        }
+    }
+}
+
+// "number,date,description,used-space" header, snapshot 0 is the live system
+fn parse_snapper_list(output: &str, tool: SnapshotTool) -> Vec<SnapshotEntry> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.splitn(4, ',').collect();
+            if cols.len() < 3 || cols[0] == "0" {
+                return None;
+            }
+            // The description may itself contain commas, used-space is the last column.
+            let (description, size) = match line.splitn(3, ',').nth(2)?.rsplit_once(',') {
+                Some((d, s)) => (d.to_string(), s.trim().parse::<u64>().ok()),
+                None => (cols[2].to_string(), None),
+            };
+            Some(SnapshotEntry {
+                tool,
+                snapshot_id: cols[0].trim().to_string(),
+                date: cols[1].trim().to_string(),
+                description,
+                size_bytes: size,
+                transaction: None,
+            })
+        })
+        .collect()
+}
+
+struct TimeshiftListing {
+    btrfs_mode: bool,
+    entries: Vec<SnapshotEntry>,
+}
+
+/// ```text
+/// Path   : /run/timeshift/backup
+/// Mode   : BTRFS
+/// Num     Name                 Tags  Description
+/// 0    >  2024-05-01_10-00-00  O     linuxhub pre-update
+/// ```
+fn parse_timeshift_list(output: &str) -> TimeshiftListing {
+    let mut listing = TimeshiftListing {
+        btrfs_mode: true,
+        entries: Vec::new(),
+    };
+
+    for line in output.lines() {
+        if let Some(mode) = line.strip_prefix("Mode") {
+            listing.btrfs_mode = mode.contains("BTRFS");
+            continue;
+        }
+
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() < 3 || tokens[1] != ">" || tokens[0].parse::<u32>().is_err() {
+            continue;
+        }
+        let name = tokens[2];
+        let rest = &tokens[3..];
+        let description = match rest.first() {
+            Some(tags) if tags.chars().all(|c| "OBHDWM".contains(c)) => rest[1..].join(" "),
+            _ => rest.join(" "),
+        };
+
+        listing.entries.push(SnapshotEntry {
+            tool: SnapshotTool::Timeshift,
+            snapshot_id: name.to_string(),
+            date: name_to_date(name),
+            description,
+            size_bytes: None,
+            transaction: None,
+        });
+    }
+
+    listing
+}
+
+// Timeshift and linuxhub's Btrfs snapshots are named "2024-05-01_10-00-00"
+fn name_to_date(name: &str) -> String {
+    chrono::NaiveDateTime::parse_from_str(name, "%Y-%m-%d_%H-%M-%S")
+        .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|_| name.to_string())
+}
+
+/// Resolves a raw Btrfs snapshot id to a subvolume directly inside BTRFS_SNAPSHOT_DIR.
+/// Ids come from the frontend and end up in root commands, so a prefix check is not
+/// enough: `..` components and symlinks could point anywhere.
+fn btrfs_snapshot_path(snapshot_id: &str) -> Result<PathBuf, String> {
+    let path = Path::new(snapshot_id);
+    if !path.is_absolute() || path.components().any(|c| !matches!(c, Component::RootDir | Component::Normal(_))) {
+        return Err(format!("Invalid snapshot path {}", snapshot_id));
+    }
+    // The snapshot directory is often root-only; then the lexical check above has to do,
+    // which is safe because nobody but root can plant symlinks in there.
+    let resolved = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if resolved.parent() != Some(Path::new(BTRFS_SNAPSHOT_DIR)) {
+        return Err(format!("Refusing to touch {} outside {}", snapshot_id, BTRFS_SNAPSHOT_DIR));
+    }
+    Ok(resolved)
+}
+
+/// Snapper ids are numbers and Timeshift ids are timestamps; neither may contain a path.
+fn check_snapshot_name(snapshot_id: &str) -> Result<(), String> {
+    let valid = !snapshot_id.is_empty()
+        && snapshot_id != ".."
+        && snapshot_id.chars().all(|c| c.is_ascii_alphanumeric() || "-_:.".contains(c));
+    if valid { Ok(()) } else { Err(format!("Invalid snapshot id {}", snapshot_id)) }
+}
+
+/// `ls -1` of the pacman local database inside a snapshot.
+fn snapshot_db_listing(tool: SnapshotTool, snapshot_id: &str) -> Result<String, String> {
+    let root = match tool {
+        SnapshotTool::Snapper => {
+            check_snapshot_name(snapshot_id)?;
+            format!("/.snapshots/{}/snapshot", snapshot_id)
+        }
+        SnapshotTool::Timeshift => return timeshift_db_listing(snapshot_id),
+        SnapshotTool::Btrfs => btrfs_snapshot_path(snapshot_id)?.to_string_lossy().into_owned(),
+    };
+    let db_path = format!("{}{}", root, PACMAN_LOCAL_DB);
+    // Snapshot directories are usually readable by root only.
+    run_cmd("ls", &["-1", &db_path]).or_else(|_| run_cmd("pkexec", &["ls", "-1", &db_path]))
+}
+
+/// Timeshift mounts its backup device only while it runs (under /run/timeshift/<pid>),
+/// so the device is mounted read-only in a temporary directory for the listing.
+/// In Btrfs mode the snapshots live in the top-level subvolume, hence subvolid=5.
+fn timeshift_db_listing(snapshot_id: &str) -> Result<String, String> {
+    check_snapshot_name(snapshot_id)?;
+    let (uuid, btrfs_mode) = timeshift_backup_device().ok_or("Timeshift has no backup device configured.")?;
+    let (options, root) = if btrfs_mode {
+        ("ro,subvolid=5", format!("timeshift-btrfs/snapshots/{}/@", snapshot_id))
+    } else {
+        ("ro", format!("timeshift/snapshots/{}/localhost", snapshot_id))
+    };
+    let db_path = format!("{}{}", root, PACMAN_LOCAL_DB);
+    let script = "m=$(mktemp -d) || exit 1; trap 'umount \"$m\" 2>/dev/null; rmdir \"$m\"' EXIT; \
+                  mount -o \"$1\" UUID=\"$2\" \"$m\" && ls -1 \"$m/$3\"";
+    run_cmd("pkexec", &["sh", "-c", script, "sh", options, &uuid, &db_path])
+}
+
+// -----------------------------------------------------------------------------
+// Package comparison
+// -----------------------------------------------------------------------------
+/// Reads a pacman local database listing ("name-pkgver-pkgrel" directories).
+fn parse_local_db_entries(listing: &str) -> HashMap<String, String> {
+    listing
+        .lines()
+        .filter_map(|entry| {
+            let entry = entry.trim();
+            let mut parts = entry.rsplitn(3, '-');
+            let pkgrel = parts.next()?;
+            let pkgver = parts.next()?;
+            let name = parts.next()?;
+            Some((name.to_string(), format!("{}-{}", pkgver, pkgrel)))
+        })
+        .collect()
+}
+
+fn diff_package_sets(
+    snapshot_id: &str,
+    snapshot: &HashMap<String, String>,
+    current: &HashMap<String, String>,
+) -> SnapshotPackageDiff {
+    let mut added: Vec<String> = current.keys().filter(|n| !snapshot.contains_key(*n)).cloned().collect();
+    let mut removed: Vec<String> = snapshot.keys().filter(|n| !current.contains_key(*n)).cloned().collect();
+    let mut changed: Vec<PackageVersionChange> = snapshot
+        .iter()
+        .filter_map(|(name, old)| {
+            let new = current.get(name)?;
+            (old != new).then(|| PackageVersionChange {
+                name: name.clone(),
+                snapshot_version: old.clone(),
+                current_version: new.clone(),
+            })
+        })
+        .collect();
+
+    added.sort();
+    removed.sort();
+    changed.sort_by(|a, b| a.name.cmp(&b.name));
+
+    SnapshotPackageDiff {
+        snapshot_id: snapshot_id.to_string(),
+        added,
+        removed,
+        changed,
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Local index (app data dir)
+// -----------------------------------------------------------------------------
+fn index_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(SNAPSHOT_INDEX_FILE))
+        .map_err(|e| format!("Could not resolve app data dir: {}", e))
+}
+
+pub fn load_index(app_handle: &AppHandle) -> Vec<SnapshotRecord> {
+    index_path(app_handle)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_index(app_handle: &AppHandle, index: &[SnapshotRecord]) -> Result<(), String> {
+    let path = index_path(app_handle)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("create {}: {}", dir.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(index).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("write {}: {}", path.display(), e))
+}
+
+/// Appends one line per elevated operation to the audit log in the app data dir.
+fn audit(app_handle: &AppHandle, action: &str, tool: SnapshotTool, snapshot_id: &str, result: &Result<String, String>) {
+    let (success, message) = match result {
+        Ok(m) => (true, m.as_str()),
+        Err(e) => (false, e.as_str()),
+    };
+    let entry = AuditEntry {
+        timestamp: Utc::now().to_rfc3339(),
+        action,
+        tool,
+        snapshot_id,
+        success,
+        message,
+    };
+
+    let write = || -> Result<(), String> {
+        let dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(SNAPSHOT_AUDIT_FILE))
+            .map_err(|e| e.to_string())?;
+        let line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+        writeln!(file, "{}", line).map_err(|e| e.to_string())
+    };
+    if let Err(e) = write() {
+        eprintln!("Failed to write snapshot audit log: {}", e);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Helper: run a command and capture stdout
+// -----------------------------------------------------------------------------
+fn run_cmd(cmd: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(cmd)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute {}: {}", cmd, e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            cmd,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Runs snapshot tool commands off the async runtime; most of them wait on a pkexec prompt.
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Snapshot task failed: {}", e))?
+}
+
+// -----------------------------------------------------------------------------
+// Tauri command: which snapshot tool would be used
+// -----------------------------------------------------------------------------
+#[tauri::command]
+pub async fn get_snapshot_tool() -> Result<Option<SnapshotTool>, String> {
+    blocking(|| Ok(detect_snapshot_tool())).await
+}
+
+/// Lists snapshots of the detected tool, newest first, with their linuxhub transaction.
+#[tauri::command]
+pub async fn list_snapshots(app_handle: AppHandle) -> Result<Vec<SnapshotEntry>, String> {
+    let mut entries = blocking(|| {
+        let tool = detect_snapshot_tool().ok_or("No snapshot tool available.")?;
+        list_tool_snapshots(tool)
+    })
+    .await?;
+    let index = load_index(&app_handle);
+
+    for entry in &mut entries {
+        entry.transaction = index
+            .iter()
+            .find(|r| r.tool == entry.tool && r.snapshot_id == entry.snapshot_id)
+            .cloned();
+        if entry.description.is_empty() {
+            if let Some(record) = &entry.transaction {
+                entry.description = record.description.clone();
+            }
+        }
+    }
+    entries.reverse();
+
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn delete_snapshot(
+    app_handle: AppHandle,
+    tool: SnapshotTool,
+    snapshot_id: String,
+) -> Result<String, String> {
+    let id = snapshot_id.clone();
+    let result = blocking(move || match tool {
+        SnapshotTool::Snapper => {
+            let config = snapper_root_config().ok_or("No snapper config for / found.")?;
+            check_snapshot_name(&id)?;
+            run_cmd("pkexec", &["snapper", "-c", &config, "delete", &id])
+        }
+        SnapshotTool::Timeshift => check_snapshot_name(&id)
+            .and_then(|_| run_cmd("pkexec", &["timeshift", "--delete", "--snapshot", &id, "--scripted"])),
+        SnapshotTool::Btrfs => btrfs_snapshot_path(&id)
+            .and_then(|path| run_cmd("pkexec", &["btrfs", "subvolume", "delete", &path.to_string_lossy()])),
+    })
+    .await
+    .map(|_| format!("Snapshot {} deleted.", snapshot_id));
+
+    audit(&app_handle, "delete", tool, &snapshot_id, &result);
+    if result.is_ok() {
+        let mut index = load_index(&app_handle);
+        index.retain(|r| !(r.tool == tool && r.snapshot_id == snapshot_id));
+        save_index(&app_handle, &index)?;
+    }
+    result
+}
+
+/// Compares the package list stored in a snapshot with the running system.
+#[tauri::command]
+pub async fn compare_snapshot_packages(
+    tool: SnapshotTool,
+    snapshot_id: String,
+) -> Result<SnapshotPackageDiff, String> {
+    blocking(move || {
+        let snapshot_listing = snapshot_db_listing(tool, &snapshot_id)?;
+        let current_listing = run_cmd("ls", &["-1", PACMAN_LOCAL_DB])?;
+
+        Ok(diff_package_sets(
+            &snapshot_id,
+            &parse_local_db_entries(&snapshot_listing),
+            &parse_local_db_entries(&current_listing),
+        ))
+    })
+    .await
+}
+
+/// Makes the snapshot the system state after the next reboot. Timeshift in rsync
+/// mode cannot defer: it copies the snapshot over the running root right away, so
+/// that is refused unless `immediate` is set.
+#[tauri::command]
+pub async fn schedule_snapshot_restore(
+    app_handle: AppHandle,
+    tool: SnapshotTool,
+    snapshot_id: String,
+    immediate: Option<bool>,
+) -> Result<String, String> {
+    let id = snapshot_id.clone();
+    let result = blocking(move || {
+        let mut message = format!("Snapshot {} will be active after the next reboot.", id);
+        match tool {
+            // snapper creates a writable copy and makes it the default subvolume.
+            SnapshotTool::Snapper => {
+                let config = snapper_root_config().ok_or("No snapper config for / found.")?;
+                check_snapshot_name(&id)?;
+                run_cmd("pkexec", &["snapper", "-c", &config, "rollback", &id])
+            }
+            // In Btrfs mode Timeshift swaps the subvolumes, taking effect on reboot.
+            SnapshotTool::Timeshift => {
+                check_snapshot_name(&id)?;
+                let listing = parse_timeshift_list(&run_cmd("pkexec", &["timeshift", "--list", "--scripted"])?);
+                if !listing.btrfs_mode {
+                    if !immediate.unwrap_or(false) {
+                        return Err(
+                            "Timeshift is in rsync mode: restoring overwrites the running system immediately \
+                             instead of at the next boot. Confirm an immediate restore to continue."
+                                .to_string(),
+                        );
+                    }
+                    message = format!(
+                        "WARNING: snapshot {} was copied over the running system. Reboot now; \
+                         programs still running use a mix of old and new files.",
+                        id
+                    );
+                }
+                run_cmd("pkexec", &["timeshift", "--restore", "--snapshot", &id, "--scripted", "--yes"])
+            }
+            SnapshotTool::Btrfs => Err(
+                "Raw Btrfs snapshots cannot be rolled back automatically; restore the subvolume manually.".to_string(),
+            ),
+        }
+        .map(|_| message)
+    })
+    .await;
+
+    audit(&app_handle, "restore", tool, &snapshot_id, &result);
+    result
+}
@@ -0,0 +1,296 @@
+// src/storage.rs
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+const SYS_BLOCK: &str = "/sys/class/block";
+/// udev's database has what would otherwise need blkid as root: fs type, label, UUID, bus.
+const UDEV_DATA: &str = "/run/udev/data";
+/// dm and md devices stack on each other; nothing real goes this deep.
+const MAX_DEPTH: usize = 8;
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Clone)]
+pub struct MountEntry {
+    pub mount_point: String,
+    pub options: String,
+    /// Subvolume or bind source inside the filesystem, "/" for a plain mount.
+    pub fs_root: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BlockDevice {
+    /// Kernel name: "nvme0n1p2", "dm-0", "md127".
+    pub name: String,
+    /// "/dev/mapper/cryptroot" for dm devices, "/dev/<name>" otherwise.
+    pub path: String,
+    /// "disk", "partition", "crypt", "lvm", "dm", "raid", "loop", "rom"
+    pub kind: String,
+    pub major_minor: String,
+    pub size_bytes: u64,
+    pub read_only: bool,
+    pub removable: bool,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    /// Whole disks only.
+    pub rotational: Option<bool>,
+    /// "nvme", "sata", "usb", "mmc", "virtio", "scsi"; whole disks only.
+    pub transport: Option<String>,
+    pub partition_number: Option<u32>,
+    pub partition_label: Option<String>,
+    /// "raid1", "raid5", ... for md devices.
+    pub raid_level: Option<String>,
+    /// Mapper name of dm devices ("cryptroot", "vg-root").
+    pub dm_name: Option<String>,
+    /// "ext4", "crypto_LUKS", "LVM2_member", "linux_raid_member", "swap", ...
+    pub fs_type: Option<String>,
+    pub fs_label: Option<String>,
+    pub fs_uuid: Option<String>,
+    pub mounts: Vec<MountEntry>,
+    pub swap_active: bool,
+    /// Partitions, then whatever is stacked on top (crypt, lvm, raid).
+    pub children: Vec<BlockDevice>,
+}
+
+// -----------------------------------------------------------------------------
+// Helpers
+// -----------------------------------------------------------------------------
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn list_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.file_name().to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// udev escapes as "\x20"; mountinfo as "\040".
+fn unescape(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let raw = value.as_bytes();
+    let mut i = 0;
+    while i < raw.len() {
+        let decoded = match raw.get(i..i + 4) {
+            Some([b'\\', b'x', h, l]) => std::str::from_utf8(&[*h, *l]).ok().and_then(|s| u8::from_str_radix(s, 16).ok()),
+            Some([b'\\', a, b, c]) if [a, b, c].iter().all(|d| (b'0'..=b'7').contains(d)) => {
+                std::str::from_utf8(&[*a, *b, *c]).ok().and_then(|s| u8::from_str_radix(s, 8).ok())
+            }
+            _ => None,
+        };
+        match decoded {
+            Some(byte) => {
+                bytes.push(byte);
+                i += 4;
+            }
+            None => {
+                bytes.push(raw[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// "E:KEY=value" properties of a device from the udev database.
+fn udev_properties(major_minor: &str) -> HashMap<String, String> {
+    fs::read_to_string(Path::new(UDEV_DATA).join(format!("b{}", major_minor)))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| l.strip_prefix("E:")?.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// Mounts keyed by "major:minor". Sources are matched too, because btrfs reports an
+/// anonymous device number in mountinfo.
+fn mounts() -> (HashMap<String, Vec<MountEntry>>, HashMap<PathBuf, Vec<MountEntry>>) {
+    let mut by_dev: HashMap<String, Vec<MountEntry>> = HashMap::new();
+    let mut by_source: HashMap<PathBuf, Vec<MountEntry>> = HashMap::new();
+
+    // 36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue
+    for line in fs::read_to_string("/proc/self/mountinfo").unwrap_or_default().lines() {
+        let Some((head, tail)) = line.split_once(" - ") else { continue };
+        let head: Vec<&str> = head.split_whitespace().collect();
+        let tail: Vec<&str> = tail.split_whitespace().collect();
+        let ([_, _, dev, root, mount_point, options, ..], [_, source, ..]) = (&head[..], &tail[..]) else { continue };
+        let entry = MountEntry {
+            mount_point: unescape(mount_point),
+            options: options.to_string(),
+            fs_root: unescape(root),
+        };
+        if source.starts_with("/dev/") {
+            if let Ok(resolved) = fs::canonicalize(unescape(source)) {
+                by_source.entry(resolved).or_default().push(entry.clone());
+            }
+        }
+        by_dev.entry(dev.to_string()).or_default().push(entry);
+    }
+    (by_dev, by_source)
+}
+
+/// Kernel names of active swap devices.
+fn active_swaps() -> Vec<String> {
+    fs::read_to_string("/proc/swaps")
+        .unwrap_or_default()
+        .lines()
+        .skip(1)
+        .filter_map(|l| l.split_whitespace().next())
+        .filter_map(|path| fs::canonicalize(unescape(path)).ok())
+        .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .collect()
+}
+
+/// From where the device sits in the sysfs hierarchy, e.g.
+/// /sys/devices/pci0000:00/0000:00:14.0/usb2/2-1/.../block/sdb.
+fn transport(name: &str, sys_dir: &Path, udev: &HashMap<String, String>) -> Option<String> {
+    let real = fs::canonicalize(sys_dir).ok()?.to_string_lossy().into_owned();
+    let transport = if name.starts_with("nvme") {
+        "nvme"
+    } else if name.starts_with("mmcblk") {
+        "mmc"
+    } else if real.contains("/usb") || udev.get("ID_BUS").is_some_and(|b| b == "usb") {
+        "usb"
+    } else if real.contains("/ata") {
+        "sata"
+    } else if name.starts_with("vd") || real.contains("/virtio") {
+        "virtio"
+    } else if name.starts_with("sd") || name.starts_with("sr") {
+        "scsi"
+    } else {
+        return None;
+    };
+    Some(transport.to_string())
+}
+
+// -----------------------------------------------------------------------------
+// Tree building
+// -----------------------------------------------------------------------------
+struct Context {
+    mounts_by_dev: HashMap<String, Vec<MountEntry>>,
+    mounts_by_source: HashMap<PathBuf, Vec<MountEntry>>,
+    swaps: Vec<String>,
+}
+
+fn device(ctx: &Context, name: &str, depth: usize) -> Option<BlockDevice> {
+    let dir = Path::new(SYS_BLOCK).join(name);
+    let major_minor = read_trimmed(&dir.join("dev"))?;
+    let size_bytes = read_trimmed(&dir.join("size")).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0) * 512;
+    let udev = udev_properties(&major_minor);
+    let prop = |key: &str| udev.get(key).map(|v| unescape(v)).filter(|v| !v.is_empty());
+
+    let partition_number = read_trimmed(&dir.join("partition")).and_then(|p| p.parse().ok());
+    let dm_name = read_trimmed(&dir.join("dm/name"));
+    let dm_uuid = read_trimmed(&dir.join("dm/uuid")).unwrap_or_default();
+    let raid_level = read_trimmed(&dir.join("md/level"));
+
+    let kind = if partition_number.is_some() {
+        "partition"
+    } else if dm_name.is_some() {
+        // "CRYPT-LUKS2-<uuid>-cryptroot", "LVM-<vg uuid><lv uuid>"
+        if dm_uuid.starts_with("CRYPT-") {
+            "crypt"
+        } else if dm_uuid.starts_with("LVM-") {
+            "lvm"
+        } else {
+            "dm"
+        }
+    } else if raid_level.is_some() {
+        "raid"
+    } else if name.starts_with("loop") {
+        "loop"
+    } else if name.starts_with("sr") {
+        "rom"
+    } else {
+        "disk"
+    };
+    let whole_disk = matches!(kind, "disk" | "rom");
+
+    let path = match &dm_name {
+        Some(dm) => format!("/dev/mapper/{}", dm),
+        None => format!("/dev/{}", name),
+    };
+    let mut mounts = ctx.mounts_by_dev.get(&major_minor).cloned().unwrap_or_default();
+    if let Some(extra) = ctx.mounts_by_source.get(&PathBuf::from(format!("/dev/{}", name))) {
+        for entry in extra {
+            if !mounts.iter().any(|m| m.mount_point == entry.mount_point) {
+                mounts.push(entry.clone());
+            }
+        }
+    }
+
+    // Partitions live as subdirectories of their disk; stacked devices are "holders".
+    let mut children = Vec::new();
+    if depth < MAX_DEPTH {
+        let partitions = list_names(&dir)
+            .into_iter()
+            .filter(|entry| entry.starts_with(name) && dir.join(entry).join("partition").exists());
+        let holders = list_names(&dir.join("holders")).into_iter();
+        children = partitions.chain(holders).filter_map(|child| device(ctx, &child, depth + 1)).collect();
+    }
+
+    Some(BlockDevice {
+        name: name.to_string(),
+        path,
+        kind: kind.to_string(),
+        major_minor,
+        size_bytes,
+        read_only: read_trimmed(&dir.join("ro")).as_deref() == Some("1"),
+        removable: read_trimmed(&dir.join("removable")).as_deref() == Some("1"),
+        model: read_trimmed(&dir.join("device/model")).or_else(|| prop("ID_MODEL")).filter(|_| whole_disk),
+        serial: read_trimmed(&dir.join("device/serial"))
+            .or_else(|| prop("ID_SERIAL_SHORT"))
+            .filter(|_| whole_disk),
+        rotational: whole_disk.then(|| read_trimmed(&dir.join("queue/rotational")).as_deref() == Some("1")),
+        transport: if whole_disk { transport(name, &dir, &udev) } else { None },
+        partition_number,
+        partition_label: prop("ID_PART_ENTRY_NAME"),
+        raid_level,
+        dm_name,
+        fs_type: prop("ID_FS_TYPE"),
+        fs_label: prop("ID_FS_LABEL_ENC").or_else(|| prop("ID_FS_LABEL")),
+        fs_uuid: prop("ID_FS_UUID"),
+        mounts,
+        swap_active: ctx.swaps.iter().any(|s| s == name),
+        children,
+    })
+}
+
+/// Every block device as a tree rooted at the physical disks (and loop/zram devices);
+/// a RAID or LVM volume spanning several disks appears under each of them.
+pub fn block_devices() -> Vec<BlockDevice> {
+    let (mounts_by_dev, mounts_by_source) = mounts();
+    let ctx = Context { mounts_by_dev, mounts_by_source, swaps: active_swaps() };
+
+    list_names(Path::new(SYS_BLOCK))
+        .into_iter()
+        .filter(|name| {
+            let dir = Path::new(SYS_BLOCK).join(name);
+            // Roots: not a partition and not stacked on another device.
+            !dir.join("partition").exists()
+                && list_names(&dir.join("slaves")).is_empty()
+                && !name.starts_with("ram")
+        })
+        .filter_map(|name| device(&ctx, &name, 0))
+        .filter(|d| d.size_bytes > 0)
+        .collect()
+}
+
+// -----------------------------------------------------------------------------
+// Tauri command
+// -----------------------------------------------------------------------------
+#[tauri::command]
+pub fn get_block_devices() -> Result<Vec<BlockDevice>, String> {
+    if !Path::new(SYS_BLOCK).exists() {
+        return Err(format!("{} is not available.", SYS_BLOCK));
+    }
+    Ok(block_devices())
+}
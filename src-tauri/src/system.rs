@@ -1,33 +1,128 @@
 // system.rs - FINAL, DEFINITIVE CORRECTED VERSION
-use tauri::{AppHandle, Wry}; 
+use tauri::{AppHandle, Manager, State, Window, Wry};
 use tauri::Emitter; // NEW: Explicitly import the Emitter trait as required by the compiler
 use sysinfo::{
     Components, Disks, Networks, System, Users, CpuRefreshKind,
-    MemoryRefreshKind, RefreshKind, ProcessRefreshKind, 
+    MemoryRefreshKind, ProcessRefreshKind, ProcessesToUpdate,
 };
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+use crate::alerts::AlertEngine;
+use crate::hwmon::HwmonReader;
+use crate::metrics_history::MetricsHistory;
+use crate::network_link::{default_routes, link_info};
+use crate::power_profile::frequency_limits;
+use crate::power_supply::power_info;
 use crate::model::*; // Import the shared data models
 
 // Define the event name for the frontend to listen to
 pub const SYSTEM_INFO_EVENT: &str = "system-info-update";
 
+// -----------------------------------------------------------------------------
+// Topics: what a window wants and how often
+// -----------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorTopic {
+    Cpu,
+    Memory,
+    Disks,
+    Networks,
+    Processes,
+    Sensors,
+    Users,
+    Power,
+}
+
+/// Nothing is sampled more often than this, whatever a window asks for.
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+
+impl MonitorTopic {
+    pub const ALL: [MonitorTopic; 8] = [
+        MonitorTopic::Cpu,
+        MonitorTopic::Memory,
+        MonitorTopic::Disks,
+        MonitorTopic::Networks,
+        MonitorTopic::Processes,
+        MonitorTopic::Sensors,
+        MonitorTopic::Users,
+        MonitorTopic::Power,
+    ];
+
+    fn default_interval(self) -> Duration {
+        match self {
+            MonitorTopic::Cpu | MonitorTopic::Memory | MonitorTopic::Networks => Duration::from_secs(1),
+            MonitorTopic::Processes | MonitorTopic::Sensors => Duration::from_secs(2),
+            MonitorTopic::Disks => Duration::from_secs(10),
+            MonitorTopic::Power => Duration::from_secs(5),
+            MonitorTopic::Users => Duration::from_secs(60),
+        }
+    }
+
+    /// The SystemData fields this topic refreshes.
+    fn sections(self) -> &'static [&'static str] {
+        match self {
+            MonitorTopic::Cpu => &["cpu", "load_average", "uptime_s"],
+            MonitorTopic::Memory => &["memory"],
+            MonitorTopic::Disks => &["disks"],
+            MonitorTopic::Networks => &["networks"],
+            MonitorTopic::Processes => &["processes"],
+            MonitorTopic::Sensors => &["components", "sensors"],
+            MonitorTopic::Users => &["users"],
+            MonitorTopic::Power => &["power"],
+        }
+    }
+}
+
+/// Fields that never change while the app runs; sent once per new subscriber.
+const STATIC_SECTIONS: &[&str] = &["os_info", "boot_time_s"];
+
+pub(crate) type TopicIntervals = HashMap<MonitorTopic, Duration>;
+
+fn default_topics() -> TopicIntervals {
+    MonitorTopic::ALL.iter().map(|t| (*t, t.default_interval())).collect()
+}
+
+/// Intervals from the frontend are in milliseconds; 0 or missing means the default.
+fn topics_from_request(topics: Option<HashMap<MonitorTopic, u64>>) -> TopicIntervals {
+    match topics {
+        None => default_topics(),
+        Some(requested) => requested
+            .into_iter()
+            .map(|(topic, ms)| {
+                let interval = if ms == 0 { topic.default_interval() } else { Duration::from_millis(ms) };
+                (topic, interval.max(MIN_INTERVAL))
+            })
+            .collect(),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Sampling
+// -----------------------------------------------------------------------------
+
 /// Gathers the current state of system information into the SystemData struct.
+/// Energy-only power sources need an earlier read from the same `hwmon` reader.
 pub fn get_system_data(
-    sys: &mut System, 
-    networks: &mut Networks, 
-    disks: &Disks, 
-    components: &Components, 
-    users: &Users
+    sys: &mut System,
+    networks: &mut Networks,
+    disks: &Disks,
+    components: &Components,
+    users: &Users,
+    hwmon: &mut HwmonReader,
 ) -> SystemData {
-    
+
     // 1. Refresh what changes frequently: CPU, Memory, Processes
-    let refresh_kind = RefreshKind::nothing()
-        .with_cpu(CpuRefreshKind::everything())
-        .with_memory(MemoryRefreshKind::everything())
-        .with_processes(ProcessRefreshKind::everything()); // Fixed sysinfo argument
+    refresh_cpu(sys);
+    sys.refresh_memory_specifics(MemoryRefreshKind::everything());
+    refresh_processes(sys);
 
-    sys.refresh_specifics(refresh_kind);
-    
     networks.refresh(false); // Fixed sysinfo argument
 
     // --- 2. Gather OS Info (Static) ---
@@ -39,135 +134,549 @@ pub fn get_system_data(
         host_name: System::host_name().unwrap_or_else(|| "<unknown>".to_owned()),
     };
 
-    // --- 3. Gather Uptime and Load (Dynamic) ---
+    // --- 3. Assemble the final data structure from the per-topic gatherers ---
+    SystemData {
+        os_info,
+        boot_time_s: System::boot_time(),
+        uptime_s: System::uptime(),
+        load_average: load_average(),
+        memory: memory_info(sys),
+        cpu: cpu_snapshot(sys),
+        disks: disk_list(disks),
+        networks: network_list(networks, None),
+        processes: top_processes(sys),
+        components: component_list(components),
+        users: user_list(users),
+        power: power_info(),
+        sensors: hwmon.read(),
+    }
+}
+
+fn refresh_cpu(sys: &mut System) {
+    sys.refresh_cpu_specifics(CpuRefreshKind::nothing().with_cpu_usage().with_frequency());
+}
+
+// Only CPU usage is needed to pick the top processes; skipping disk I/O, environ
+// and cmdline keeps the monitor out of its own top list.
+fn refresh_processes(sys: &mut System) {
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing().with_cpu());
+}
+
+fn load_average() -> LoadAverage {
     let load_avg = System::load_average();
+    LoadAverage {
+        one_min: load_avg.one,
+        five_min: load_avg.five,
+        fifteen_min: load_avg.fifteen,
+    }
+}
 
-    // --- 4. Gather Memory Info (Dynamic) ---
-    let memory = MemoryInfo {
+fn memory_info(sys: &System) -> MemoryInfo {
+    MemoryInfo {
         total_kb: sys.total_memory() / 1_000,
         available_kb: sys.available_memory() / 1_000,
         used_kb: sys.used_memory() / 1_000,
         total_swap_kb: sys.total_swap() / 1_000,
         used_swap_kb: sys.used_swap() / 1_000,
-    };
+    }
+}
 
-    // --- 5. Gather CPU Info (Dynamic) ---
-    let individual_cpus: Vec<CpuDetails> = sys.cpus().iter().map(|cpu| CpuDetails {
-        name: cpu.name().to_string(),
-        usage_percent: cpu.cpu_usage(),
-        frequency_mhz: cpu.frequency(),
+fn cpu_snapshot(sys: &System) -> CpuSnapshot {
+    let individual_cpus: Vec<CpuDetails> = sys.cpus().iter().enumerate().map(|(index, cpu)| {
+        let limits = frequency_limits(index);
+        CpuDetails {
+            name: cpu.name().to_string(),
+            usage_percent: cpu.cpu_usage(),
+            frequency_mhz: cpu.frequency(),
+            min_frequency_mhz: limits.min_mhz,
+            max_frequency_mhz: limits.max_mhz,
+            scaling_min_mhz: limits.scaling_min_mhz,
+            scaling_max_mhz: limits.scaling_max_mhz,
+        }
     }).collect();
 
-    let cpu = CpuSnapshot {
+    CpuSnapshot {
         physical_cores: System::physical_core_count(),
         global_usage_percent: sys.global_cpu_usage(),
-        brand: sys.cpus().get(0).map(|c| c.brand().to_string()).unwrap_or_else(|| "<unknown>".to_owned()),
-        vendor_id: sys.cpus().get(0).map(|c| c.vendor_id().to_string()).unwrap_or_else(|| "<unknown>".to_owned()),
+        brand: sys.cpus().first().map(|c| c.brand().to_string()).unwrap_or_else(|| "<unknown>".to_owned()),
+        vendor_id: sys.cpus().first().map(|c| c.vendor_id().to_string()).unwrap_or_else(|| "<unknown>".to_owned()),
         individual_cpus,
-    };
+    }
+}
 
-    // --- 6. Gather Disk Info (Mostly Static) ---
-    let disks: Vec<DiskInfo> = disks.list().iter().map(|disk| DiskInfo {
+fn disk_list(disks: &Disks) -> Vec<DiskInfo> {
+    disks.list().iter().map(|disk| DiskInfo {
         name: disk.name().to_string_lossy().into_owned(),
         mount_point: disk.mount_point().to_string_lossy().into_owned(),
         total_gb: disk.total_space() as f64 / 1024_f64.powi(3),
         available_gb: disk.available_space() as f64 / 1024_f64.powi(3),
-    }).collect();
+    }).collect()
+}
 
-    // --- 7. Gather Network Info (Dynamic) ---
-    let networks: Vec<NetworkData> = networks.list().iter().map(|(name, data)| NetworkData {
-        interface_name: name.clone(),
-        mac_address: data.mac_address().to_string(),
-        received_bytes: data.received(),
-        total_received_bytes: data.total_received(),
-        transmitted_bytes: data.transmitted(),
-        total_transmitted_bytes: data.total_transmitted(),
-    }).collect();
+/// `elapsed` is the time since the previous refresh, for the per-second rates.
+fn network_list(networks: &Networks, elapsed: Option<Duration>) -> Vec<NetworkData> {
+    let default_routes = default_routes();
+    let secs = elapsed.map(|d| d.as_secs_f64()).filter(|s| *s > 0.0);
+    let rate = |delta: u64| secs.map(|s| delta as f64 / s).unwrap_or(0.0);
+
+    networks.list().iter().map(|(name, data)| {
+        let link = link_info(name);
+        let route = default_routes.get(name);
+        NetworkData {
+            interface_name: name.clone(),
+            mac_address: data.mac_address().to_string(),
+            received_bytes: data.received(),
+            total_received_bytes: data.total_received(),
+            transmitted_bytes: data.transmitted(),
+            total_transmitted_bytes: data.total_transmitted(),
+            received_bytes_per_s: rate(data.received()),
+            transmitted_bytes_per_s: rate(data.transmitted()),
+            received_packets_per_s: rate(data.packets_received()),
+            transmitted_packets_per_s: rate(data.packets_transmitted()),
+            addresses: data.ip_networks().iter().map(|ip| InterfaceAddress {
+                address: ip.addr.to_string(),
+                prefix: ip.prefix,
+                family: if ip.addr.is_ipv4() { "ipv4" } else { "ipv6" }.to_string(),
+            }).collect(),
+            mtu: data.mtu(),
+            operstate: link.operstate,
+            speed_mbps: link.speed_mbps,
+            duplex: link.duplex,
+            wireless: link.wireless,
+            is_default_route: route.is_some(),
+            default_gateway_v4: route.and_then(|r| r.gateway_v4.clone()),
+            default_gateway_v6: route.and_then(|r| r.gateway_v6.clone()),
+        }
+    }).collect()
+}
 
-    // --- 8. Gather Processes (Dynamic, Top 10 by CPU) ---
+// Top 10 by CPU
+fn top_processes(sys: &System) -> Vec<ProcessSnapshot> {
     let mut processes_vec: Vec<_> = sys.processes().values().collect();
     processes_vec.sort_by(|a, b| b.cpu_usage().partial_cmp(&a.cpu_usage()).unwrap_or(std::cmp::Ordering::Equal));
-    let processes: Vec<ProcessSnapshot> = processes_vec.iter().take(10).map(|p| ProcessSnapshot {
+    processes_vec.iter().take(10).map(|p| ProcessSnapshot {
         pid: p.pid().as_u32(),
         name: p.name().to_string_lossy().into_owned(),
         status: format!("{:?}", p.status()),
         cpu_usage_percent: p.cpu_usage(),
-    }).collect();
+    }).collect()
+}
 
-    // --- 9. Gather Components (Temperature, Dynamic) ---
-    let components: Vec<ComponentSnapshot> = components.iter().filter_map(|c| {
-        if let Some(temp) = c.temperature() {
-            Some(ComponentSnapshot {
-                label: c.label().to_string(),
-                temperature_c: temp,
-                max_c: c.max().unwrap_or(0.0),
-                critical_c: c.critical(),
-            })
-        } else {
-            None
-        }
-    }).collect();
+fn component_list(components: &Components) -> Vec<ComponentSnapshot> {
+    components.iter().filter_map(|c| {
+        c.temperature().map(|temp| ComponentSnapshot {
+            label: c.label().to_string(),
+            temperature_c: temp,
+            max_c: c.max().unwrap_or(0.0),
+            critical_c: c.critical(),
+        })
+    }).collect()
+}
 
-    // --- 10. Gather Users (Static) ---
-    let users: Vec<UserInfo> = users.list().iter().map(|u| UserInfo {
+fn user_list(users: &Users) -> Vec<UserInfo> {
+    users.list().iter().map(|u| UserInfo {
         name: u.name().to_string(),
         groups: u.groups().iter().map(|g| g.name().to_string()).collect(),
-    }).collect();
+    }).collect()
+}
 
-    // --- 11. Assemble the final data structure ---
-    SystemData {
-        os_info,
-        boot_time_s: System::boot_time(),
-        uptime_s: System::uptime(),
-        load_average: LoadAverage {
-            one_min: load_avg.one,
-            five_min: load_avg.five,
-            fifteen_min: load_avg.fifteen,
-        },
-        memory,
-        cpu,
-        disks,
-        networks,
-        processes,
-        components,
-        users,
-    }
-}
-
-/// The asynchronous main function to run the live system monitoring loop.
-pub fn run_live_monitor(app_handle: AppHandle<Wry>) {
-    std::thread::spawn(move || {
+// -----------------------------------------------------------------------------
+// Live monitor: one sampling task shared by every window
+// -----------------------------------------------------------------------------
+const TICK: Duration = MIN_INTERVAL;
+
+/// sysinfo handles reused between samples so CPU usage and network deltas are meaningful,
+/// plus the latest value of every section.
+struct Sampler {
+    sys: System,
+    networks: Networks,
+    disks: Disks,
+    components: Components,
+    users: Users,
+    networks_refreshed: Instant,
+    hwmon: HwmonReader,
+    data: SystemData,
+}
+
+impl Sampler {
+    fn new() -> Self {
         let mut sys = System::new();
+        let mut networks = Networks::new_with_refreshed_list();
         let disks = Disks::new_with_refreshed_list();
         let components = Components::new_with_refreshed_list();
         let users = Users::new_with_refreshed_list();
-        let mut networks = Networks::new_with_refreshed_list();
+        let mut hwmon = HwmonReader::default();
+        let data = get_system_data(&mut sys, &mut networks, &disks, &components, &users, &mut hwmon);
+        Sampler { sys, networks, disks, components, users, networks_refreshed: Instant::now(), hwmon, data }
+    }
 
-        // Main monitoring loop
-        loop {
-            let data = get_system_data(&mut sys, &mut networks, &disks, &components, &users);
-
-            match serde_json::to_string(&data) {
-                Ok(json) => {
-                    // FIX: The Emitter trait is now in scope, allowing the use of .emit()
-                    if let Err(e) = app_handle.emit(SYSTEM_INFO_EVENT, json) {
-                        eprintln!("Failed to emit system info event: {}", e);
-                    }
-                },
-                Err(e) => {
-                    eprintln!("Failed to serialize system data: {}", e);
-                }
+    /// Refreshes only what `topic` needs and updates its sections.
+    fn refresh(&mut self, topic: MonitorTopic) {
+        match topic {
+            MonitorTopic::Cpu => {
+                refresh_cpu(&mut self.sys);
+                self.data.cpu = cpu_snapshot(&self.sys);
+                self.data.load_average = load_average();
+                self.data.uptime_s = System::uptime();
+            }
+            MonitorTopic::Memory => {
+                self.sys.refresh_memory_specifics(MemoryRefreshKind::everything());
+                self.data.memory = memory_info(&self.sys);
+            }
+            MonitorTopic::Disks => {
+                self.disks.refresh(true);
+                self.data.disks = disk_list(&self.disks);
+            }
+            MonitorTopic::Networks => {
+                self.networks.refresh(true);
+                let elapsed = self.networks_refreshed.elapsed();
+                self.networks_refreshed = Instant::now();
+                self.data.networks = network_list(&self.networks, Some(elapsed));
+            }
+            MonitorTopic::Processes => {
+                refresh_processes(&mut self.sys);
+                self.data.processes = top_processes(&self.sys);
+            }
+            MonitorTopic::Sensors => {
+                self.components.refresh(true);
+                self.data.components = component_list(&self.components);
+                self.data.sensors = self.hwmon.read();
+            }
+            MonitorTopic::Users => {
+                self.users.refresh();
+                self.data.users = user_list(&self.users);
+            }
+            MonitorTopic::Power => {
+                self.data.power = power_info();
+            }
+        }
+    }
+}
+
+/// State read by the sampling task on every tick.
+#[derive(Default)]
+struct MonitorShared {
+    paused: AtomicBool,
+    /// Union of every window's topics, with the shortest interval asked for.
+    topics: Mutex<TopicIntervals>,
+    /// Set when a window subscribes so it gets every section, not just changes.
+    send_everything: AtomicBool,
+    history: Arc<Mutex<MetricsHistory>>,
+    alerts: Arc<Mutex<AlertEngine>>,
+}
+
+struct WindowSubscription {
+    /// start_system_monitor calls not yet matched by a stop.
+    count: usize,
+    topics: TopicIntervals,
+}
+
+#[derive(Default)]
+struct MonitorState {
+    subscribers: HashMap<String, WindowSubscription>,
+    token: Option<CancellationToken>,
+    shared: Arc<MonitorShared>,
+}
+
+/// Managed singleton; sampling runs while at least one window is subscribed.
+#[derive(Default)]
+pub struct SystemMonitor {
+    state: Mutex<MonitorState>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MonitorStatus {
+    pub running: bool,
+    pub paused: bool,
+    pub subscribers: usize,
+    /// Effective interval per topic, in milliseconds.
+    pub topics: HashMap<MonitorTopic, u64>,
+}
+
+impl SystemMonitor {
+    fn status(state: &MonitorState) -> MonitorStatus {
+        MonitorStatus {
+            running: state.token.is_some(),
+            paused: state.shared.paused.load(Ordering::Relaxed),
+            subscribers: state.subscribers.values().map(|s| s.count).sum(),
+            topics: state
+                .shared
+                .topics
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(t, d)| (*t, d.as_millis() as u64))
+                .collect(),
+        }
+    }
+
+    fn merge_topics(state: &MonitorState) {
+        let mut merged = TopicIntervals::new();
+        for (topic, interval) in state.subscribers.values().flat_map(|s| s.topics.iter()) {
+            let entry = merged.entry(*topic).or_insert(*interval);
+            *entry = (*entry).min(*interval);
+        }
+        *state.shared.topics.lock().unwrap() = merged;
+    }
+
+    fn subscribe(&self, app_handle: AppHandle<Wry>, window: &str, topics: TopicIntervals) -> MonitorStatus {
+        let mut state = self.state.lock().unwrap();
+        let subscription = state
+            .subscribers
+            .entry(window.to_string())
+            .or_insert_with(|| WindowSubscription { count: 0, topics: TopicIntervals::new() });
+        subscription.count += 1;
+        subscription.topics.extend(topics);
+        Self::merge_topics(&state);
+        state.shared.send_everything.store(true, Ordering::Relaxed);
+
+        if state.token.is_none() {
+            state.shared.history.lock().unwrap().ensure_loaded(&app_handle);
+            let token = CancellationToken::new();
+            state.shared.paused.store(false, Ordering::Relaxed);
+            tauri::async_runtime::spawn(run_live_monitor(app_handle, token.clone(), state.shared.clone()));
+            state.token = Some(token);
+        }
+        Self::status(&state)
+    }
+
+    fn unsubscribe(&self, window: &str, all: bool) -> MonitorStatus {
+        let mut state = self.state.lock().unwrap();
+        if let Some(subscription) = state.subscribers.get_mut(window) {
+            subscription.count = if all { 0 } else { subscription.count.saturating_sub(1) };
+            if subscription.count == 0 {
+                state.subscribers.remove(window);
             }
+        }
+        Self::merge_topics(&state);
+
+        if state.subscribers.is_empty() {
+            if let Some(token) = state.token.take() {
+                token.cancel();
+            }
+        }
+        Self::status(&state)
+    }
 
-            // Wait for the next update (e.g., every 1 second)
-            std::thread::sleep(Duration::from_secs(1));
+    pub fn history(&self) -> Arc<Mutex<MetricsHistory>> {
+        self.state.lock().unwrap().shared.history.clone()
+    }
+
+    pub fn alerts(&self) -> Arc<Mutex<AlertEngine>> {
+        self.state.lock().unwrap().shared.alerts.clone()
+    }
+
+    /// Holds (Some) or drops (None) a subscription that belongs to no window,
+    /// such as the alert engine's, replacing its topics instead of stacking counts.
+    pub fn set_background_subscription(&self, app_handle: AppHandle<Wry>, label: &str, topics: Option<TopicIntervals>) {
+        let Some(topics) = topics else {
+            self.unsubscribe(label, true);
+            return;
+        };
+        if self.set_topics(label, topics.clone()).is_err() {
+            self.subscribe(app_handle, label, topics);
         }
-    });
+    }
+
+    /// Drops every subscription held by a window that was closed without calling stop.
+    pub fn release_window(&self, window: &str) {
+        self.unsubscribe(window, true);
+    }
+
+    fn set_topics(&self, window: &str, topics: TopicIntervals) -> Result<MonitorStatus, String> {
+        let mut state = self.state.lock().unwrap();
+        let subscription = state
+            .subscribers
+            .get_mut(window)
+            .ok_or("This window has not started the system monitor.")?;
+        subscription.topics = topics;
+        Self::merge_topics(&state);
+        state.shared.send_everything.store(true, Ordering::Relaxed);
+        Ok(Self::status(&state))
+    }
+
+    fn set_paused(&self, paused: bool) -> MonitorStatus {
+        let state = self.state.lock().unwrap();
+        state.shared.paused.store(paused, Ordering::Relaxed);
+        Self::status(&state)
+    }
+
+    /// The sampling task ended without being stopped (sampling failed or panicked):
+    /// drop its token and the subscriptions it served, so the status no longer reports
+    /// it running and the next start_system_monitor spawns a new one.
+    fn sampling_ended(&self, token: &CancellationToken) {
+        let mut state = self.state.lock().unwrap();
+        // Cancelled tokens were already taken by unsubscribe.
+        if token.is_cancelled() {
+            return;
+        }
+        token.cancel();
+        state.token = None;
+        state.subscribers.clear();
+        Self::merge_topics(&state);
+    }
+}
+
+/// Reports the end of run_live_monitor however it exits, including by panic.
+struct SamplingGuard {
+    app_handle: AppHandle<Wry>,
+    token: CancellationToken,
 }
 
-// Command to start the monitoring (will be called from lib.rs)
+impl Drop for SamplingGuard {
+    fn drop(&mut self) {
+        self.app_handle.state::<SystemMonitor>().sampling_ended(&self.token);
+    }
+}
+
+/// Refreshes each subscribed topic when it is due and emits the sections whose
+/// value changed since the last emit, as a partial SystemData object.
+async fn run_live_monitor(app_handle: AppHandle<Wry>, token: CancellationToken, shared: Arc<MonitorShared>) {
+    let _guard = SamplingGuard { app_handle: app_handle.clone(), token: token.clone() };
+    let Ok(mut sampler) = tokio::task::spawn_blocking(Sampler::new).await else {
+        return;
+    };
+    let mut last_sent: Map<String, Value> = Map::new();
+    let mut next_due: HashMap<MonitorTopic, Instant> = HashMap::new();
+    let mut interval = tokio::time::interval(TICK);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        if shared.paused.load(Ordering::Relaxed) {
+            continue;
+        }
+
+        let now = Instant::now();
+        let topics = shared.topics.lock().unwrap().clone();
+        let due: Vec<MonitorTopic> = topics
+            .iter()
+            .filter(|(topic, _)| next_due.get(topic).is_none_or(|at| *at <= now))
+            .map(|(topic, _)| *topic)
+            .collect();
+        for topic in &due {
+            next_due.insert(*topic, now + topics[topic]);
+        }
+        let send_everything = shared.send_everything.swap(false, Ordering::Relaxed);
+        if due.is_empty() && !send_everything {
+            continue;
+        }
+
+        // Refreshing blocks for a few milliseconds; keep it off the async workers.
+        let due_topics = due.clone();
+        let history = shared.history.clone();
+        let alerts = shared.alerts.clone();
+        let alert_handle = app_handle.clone();
+        let (returned, current) = match tokio::task::spawn_blocking(move || {
+            for topic in due_topics {
+                sampler.refresh(topic);
+            }
+            history.lock().unwrap().record(&sampler.data);
+            alerts.lock().unwrap().evaluate(&sampler.data, &alert_handle);
+            let current = serde_json::to_value(&sampler.data);
+            (sampler, current)
+        })
+        .await
+        {
+            Ok((sampler, Ok(Value::Object(current)))) => (sampler, current),
+            Ok((_, _)) => {
+                eprintln!("Failed to serialize system data");
+                break;
+            }
+            Err(e) => {
+                eprintln!("System monitor sampling failed: {}", e);
+                break;
+            }
+        };
+        sampler = returned;
+
+        // Stopped while sampling: nobody is listening anymore.
+        if token.is_cancelled() {
+            break;
+        }
+
+        let mut payload = Map::new();
+        let sections = due.iter().flat_map(|t| t.sections().iter());
+        let sections: Vec<&str> = if send_everything {
+            // A new subscriber starts from nothing: static sections plus every subscribed one.
+            STATIC_SECTIONS
+                .iter()
+                .chain(topics.keys().flat_map(|t| t.sections().iter()))
+                .copied()
+                .collect()
+        } else {
+            sections.copied().collect()
+        };
+        for key in sections {
+            let Some(value) = current.get(key) else { continue };
+            if send_everything || last_sent.get(key) != Some(value) {
+                payload.insert(key.to_string(), value.clone());
+                last_sent.insert(key.to_string(), value.clone());
+            }
+        }
+        if payload.is_empty() {
+            continue;
+        }
+
+        match serde_json::to_string(&payload) {
+            Ok(json) => {
+                if let Err(e) = app_handle.emit(SYSTEM_INFO_EVENT, json) {
+                    eprintln!("Failed to emit system info event: {}", e);
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to serialize system data: {}", e);
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tauri commands
+// -----------------------------------------------------------------------------
+
+/// Subscribes the calling window; the first subscriber starts sampling. `topics`
+/// maps topic names to intervals in milliseconds and defaults to every topic.
+#[tauri::command]
+pub fn start_system_monitor(
+    app_handle: AppHandle<Wry>,
+    window: Window<Wry>,
+    monitor: State<'_, SystemMonitor>,
+    topics: Option<HashMap<MonitorTopic, u64>>,
+) -> Result<MonitorStatus, String> {
+    Ok(monitor.subscribe(app_handle, window.label(), topics_from_request(topics)))
+}
+
+/// Replaces the calling window's topics; an empty map keeps the subscription but samples nothing for it.
+#[tauri::command]
+pub fn set_system_monitor_topics(
+    window: Window<Wry>,
+    monitor: State<'_, SystemMonitor>,
+    topics: HashMap<MonitorTopic, u64>,
+) -> Result<MonitorStatus, String> {
+    monitor.set_topics(window.label(), topics_from_request(Some(topics)))
+}
+
+/// Drops one subscription of the calling window; the last one stops sampling.
 #[tauri::command]
-pub fn start_system_monitor(app_handle: AppHandle<Wry>) -> Result<(), String> {
-    run_live_monitor(app_handle);
-    Ok(())
-}
\ No newline at end of file
+pub fn stop_system_monitor(window: Window<Wry>, monitor: State<'_, SystemMonitor>) -> Result<MonitorStatus, String> {
+    Ok(monitor.unsubscribe(window.label(), false))
+}
+
+#[tauri::command]
+pub fn pause_system_monitor(monitor: State<'_, SystemMonitor>) -> Result<MonitorStatus, String> {
+    Ok(monitor.set_paused(true))
+}
+
+#[tauri::command]
+pub fn resume_system_monitor(monitor: State<'_, SystemMonitor>) -> Result<MonitorStatus, String> {
+    Ok(monitor.set_paused(false))
+}
+
+#[tauri::command]
+pub fn get_system_monitor_status(monitor: State<'_, SystemMonitor>) -> Result<MonitorStatus, String> {
+    Ok(SystemMonitor::status(&monitor.state.lock().unwrap()))
+}
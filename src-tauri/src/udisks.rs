@@ -0,0 +1,381 @@
+// src/udisks.rs
+use futures::StreamExt;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Wry};
+use zbus::fdo::ObjectManagerProxy;
+use zbus::message::Type as MessageType;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+use zbus::{Connection, MatchRule, MessageStream};
+
+// -----------------------------------------------------------------------------
+// Configuration
+// -----------------------------------------------------------------------------
+pub const STORAGE_EVENT: &str = "storage-changed";
+const UDISKS: &str = "org.freedesktop.UDisks2";
+const UDISKS_PATH: &str = "/org/freedesktop/UDisks2";
+const MANAGER_PATH: &str = "/org/freedesktop/UDisks2/Manager";
+const BLOCK: &str = "org.freedesktop.UDisks2.Block";
+const FILESYSTEM: &str = "org.freedesktop.UDisks2.Filesystem";
+const ENCRYPTED: &str = "org.freedesktop.UDisks2.Encrypted";
+const DRIVE: &str = "org.freedesktop.UDisks2.Drive";
+/// Property changes on other interfaces (SMART polling on Drive.Ata, job progress)
+/// do not change what the storage panel shows.
+const WATCHED_INTERFACES: &[&str] = &[BLOCK, FILESYSTEM, ENCRYPTED, DRIVE];
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+type ManagedObjects = HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>>;
+
+// -----------------------------------------------------------------------------
+// Data structures
+// -----------------------------------------------------------------------------
+#[derive(Debug, Serialize, Clone)]
+pub struct StorageEvent {
+    /// "added", "removed", "changed", or "reset" after UDisks2 came back, when
+    /// everything should be reloaded.
+    pub action: String,
+    pub object_path: String,
+    /// "/dev/sdb1" for block devices, None for drives.
+    pub device: Option<String>,
+    pub interfaces: Vec<String>,
+}
+
+// -----------------------------------------------------------------------------
+// D-Bus helpers
+// -----------------------------------------------------------------------------
+
+/// UDisks errors carry a readable description; the rest is D-Bus noise.
+fn dbus_error(context: &str, e: zbus::Error) -> String {
+    match e {
+        zbus::Error::MethodError(name, Some(description), _) => {
+            if name.as_str().ends_with(".NotAuthorizedDismissed") {
+                format!("{}: authentication cancelled.", context)
+            } else {
+                format!("{}: {}", context, description)
+            }
+        }
+        other => format!("{}: {}", context, other),
+    }
+}
+
+async fn connect() -> Result<Connection, String> {
+    Connection::system().await.map_err(|e| dbus_error("Cannot reach the system bus", e))
+}
+
+async fn managed_objects(conn: &Connection) -> Result<ManagedObjects, String> {
+    let proxy = ObjectManagerProxy::builder(conn)
+        .destination(UDISKS)
+        .and_then(|b| b.path(UDISKS_PATH))
+        .map_err(|e| dbus_error("UDisks2", e))?
+        .build()
+        .await
+        .map_err(|e| dbus_error("UDisks2 is not available", e))?;
+    let objects = proxy.get_managed_objects().await.map_err(|e| dbus_error("UDisks2 is not available", zbus::Error::from(e)))?;
+    Ok(objects
+        .into_iter()
+        .map(|(path, interfaces)| {
+            let interfaces = interfaces.into_iter().map(|(name, props)| (name.to_string(), props)).collect();
+            (path, interfaces)
+        })
+        .collect())
+}
+
+/// Calls a UDisks2 method; "auth.no_user_interaction" stays false so polkit can prompt.
+async fn call<B, R>(conn: &Connection, path: &ObjectPath<'_>, interface: &str, method: &str, body: &B) -> Result<R, zbus::Error>
+where
+    B: serde::Serialize + zbus::zvariant::DynamicType,
+    R: for<'d> zbus::zvariant::DynamicDeserialize<'d>,
+{
+    let reply = conn.call_method(Some(UDISKS), path, Some(interface), method, body).await?;
+    reply.body().deserialize::<R>()
+}
+
+fn property<'a>(objects: &'a ManagedObjects, path: &ObjectPath<'_>, interface: &str, name: &str) -> Option<&'a OwnedValue> {
+    objects.iter().find(|(p, _)| p.as_ref() == *path)?.1.get(interface)?.get(name)
+}
+
+/// Byte-string properties (Device, MountPoints) are NUL terminated.
+fn bytes_to_string(bytes: Vec<u8>) -> String {
+    String::from_utf8_lossy(bytes.strip_suffix(&[0]).unwrap_or(&bytes)).into_owned()
+}
+
+fn device_of(objects: &ManagedObjects, path: &ObjectPath<'_>) -> Option<String> {
+    let value = property(objects, path, BLOCK, "Device")?;
+    Vec::<u8>::try_from(value.clone()).ok().map(bytes_to_string)
+}
+
+fn object_path_property(objects: &ManagedObjects, path: &ObjectPath<'_>, interface: &str, name: &str) -> Option<OwnedObjectPath> {
+    let value = <&ObjectPath>::try_from(property(objects, path, interface, name)?).ok()?;
+    (value.as_str() != "/").then(|| value.clone().into())
+}
+
+fn mount_points(objects: &ManagedObjects, path: &ObjectPath<'_>) -> Vec<String> {
+    property(objects, path, FILESYSTEM, "MountPoints")
+        .and_then(|v| Vec::<Vec<u8>>::try_from(v.clone()).ok())
+        .map(|points| points.into_iter().map(bytes_to_string).collect())
+        .unwrap_or_default()
+}
+
+/// "/dev/sdb1" (or a /dev/disk/by-* link) to its UDisks2 block object.
+async fn resolve_block(conn: &Connection, device: &str) -> Result<OwnedObjectPath, String> {
+    if !device.starts_with("/dev/") {
+        return Err(format!("'{}' is not a device path.", device));
+    }
+    let spec = HashMap::from([("path", Value::from(device))]);
+    let options: HashMap<&str, Value> = HashMap::new();
+    let manager = ObjectPath::from_static_str_unchecked(MANAGER_PATH);
+    let found: Vec<OwnedObjectPath> = call(conn, &manager, "org.freedesktop.UDisks2.Manager", "ResolveDevice", &(spec, options))
+        .await
+        .map_err(|e| dbus_error(&format!("Cannot resolve {}", device), e))?;
+    found.into_iter().next().ok_or_else(|| format!("UDisks2 does not know {}.", device))
+}
+
+fn no_options() -> HashMap<&'static str, Value<'static>> {
+    HashMap::new()
+}
+
+async fn unmount_block(conn: &Connection, path: &ObjectPath<'_>, device: &str, force: bool) -> Result<(), String> {
+    let options = HashMap::from([("force", Value::from(force))]);
+    call::<_, ()>(conn, path, FILESYSTEM, "Unmount", &(options,))
+        .await
+        .map_err(|e| dbus_error(&format!("Failed to unmount {}", device), e))
+}
+
+/// Unmounts every filesystem on `drive` and locks its unlocked LUKS volumes, the way
+/// file managers do before ejecting or powering off.
+async fn release_drive(conn: &Connection, drive: &ObjectPath<'_>) -> Result<(), String> {
+    let objects = managed_objects(conn).await?;
+    let blocks: Vec<&OwnedObjectPath> = objects
+        .keys()
+        .filter(|p| object_path_property(&objects, p, BLOCK, "Drive").is_some_and(|d| d.as_ref() == *drive))
+        .collect();
+
+    for block in blocks {
+        let device = device_of(&objects, block).unwrap_or_else(|| block.to_string());
+        if let Some(cleartext) = object_path_property(&objects, block, ENCRYPTED, "CleartextDevice") {
+            if !mount_points(&objects, &cleartext).is_empty() {
+                let clear_device = device_of(&objects, &cleartext).unwrap_or_else(|| cleartext.to_string());
+                unmount_block(conn, &cleartext, &clear_device, false).await?;
+            }
+            call::<_, ()>(conn, block, ENCRYPTED, "Lock", &(no_options(),))
+                .await
+                .map_err(|e| dbus_error(&format!("Failed to lock {}", device), e))?;
+        }
+        if !mount_points(&objects, block).is_empty() {
+            unmount_block(conn, block, &device, false).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn drive_of(conn: &Connection, device: &str) -> Result<OwnedObjectPath, String> {
+    let block = resolve_block(conn, device).await?;
+    let objects = managed_objects(conn).await?;
+    object_path_property(&objects, &block, BLOCK, "Drive").ok_or_else(|| format!("{} is not on a physical drive.", device))
+}
+
+// -----------------------------------------------------------------------------
+// Hotplug events
+// -----------------------------------------------------------------------------
+
+/// Streams UDisks2 object and property changes as STORAGE_EVENT for the lifetime of
+/// the app. When UDisks2 restarts (package upgrade, crash) or the bus connection
+/// drops, the watch is set up again and a "reset" event tells listeners to reload.
+pub async fn watch_storage(app_handle: AppHandle<Wry>) {
+    let mut connected_before = false;
+    loop {
+        let result = watch_session(&app_handle, connected_before).await;
+        connected_before = true;
+        match result {
+            Ok(()) => eprintln!("UDisks2 went away, watching again in {:?}", RECONNECT_DELAY),
+            Err(e) => eprintln!("Storage hotplug events unavailable, retrying in {:?}: {}", RECONNECT_DELAY, e),
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Watches until UDisks2 loses or changes its bus name, or the connection ends.
+async fn watch_session(app_handle: &AppHandle<Wry>, reconnect: bool) -> Result<(), String> {
+    let conn = connect().await?;
+    let rule = MatchRule::builder()
+        .msg_type(MessageType::Signal)
+        .sender(UDISKS)
+        .and_then(|b| b.path_namespace(UDISKS_PATH))
+        .map_err(|e| dbus_error("UDisks2", e))?
+        .build();
+    let mut stream = MessageStream::for_match_rule(rule, &conn, None)
+        .await
+        .map_err(|e| dbus_error("Cannot watch UDisks2", e))?;
+    let owner_rule = MatchRule::builder()
+        .msg_type(MessageType::Signal)
+        .sender("org.freedesktop.DBus")
+        .and_then(|b| b.member("NameOwnerChanged"))
+        .and_then(|b| b.arg(0, UDISKS))
+        .map_err(|e| dbus_error("UDisks2", e))?
+        .build();
+    let mut owner_changes = MessageStream::for_match_rule(owner_rule, &conn, None)
+        .await
+        .map_err(|e| dbus_error("Cannot watch UDisks2", e))?;
+
+    // Removed objects no longer have a Device property to read.
+    let objects = managed_objects(&conn).await?;
+    let mut devices: HashMap<String, String> = objects
+        .keys()
+        .filter_map(|path| Some((path.to_string(), device_of(&objects, path)?)))
+        .collect();
+
+    if reconnect {
+        let reset = StorageEvent {
+            action: "reset".into(),
+            object_path: UDISKS_PATH.into(),
+            device: None,
+            interfaces: Vec::new(),
+        };
+        if let Err(e) = app_handle.emit(STORAGE_EVENT, &reset) {
+            eprintln!("Failed to emit storage event: {}", e);
+        }
+    }
+
+    loop {
+        let message = tokio::select! {
+            message = stream.next() => message,
+            // Any owner change invalidates the object paths seen so far.
+            _ = owner_changes.next() => return Ok(()),
+        };
+        let Some(message) = message else { return Ok(()) };
+        let Ok(message) = message else { continue };
+        let header = message.header();
+        let Some(member) = header.member().map(|m| m.to_string()) else { continue };
+
+        let event = match member.as_str() {
+            "InterfacesAdded" => {
+                let Ok((path, interfaces)) =
+                    message.body().deserialize::<(OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>)>()
+                else {
+                    continue;
+                };
+                let device = interfaces
+                    .get(BLOCK)
+                    .and_then(|props| props.get("Device"))
+                    .and_then(|v| Vec::<u8>::try_from(v.clone()).ok())
+                    .map(bytes_to_string);
+                if let Some(device) = &device {
+                    devices.insert(path.to_string(), device.clone());
+                }
+                StorageEvent {
+                    action: "added".into(),
+                    object_path: path.to_string(),
+                    device,
+                    interfaces: interfaces.into_keys().collect(),
+                }
+            }
+            "InterfacesRemoved" => {
+                let Ok((path, interfaces)) = message.body().deserialize::<(OwnedObjectPath, Vec<String>)>() else { continue };
+                let device = if interfaces.iter().any(|i| i == BLOCK) {
+                    devices.remove(path.as_str())
+                } else {
+                    devices.get(path.as_str()).cloned()
+                };
+                StorageEvent { action: "removed".into(), object_path: path.to_string(), device, interfaces }
+            }
+            "PropertiesChanged" => {
+                let Ok((interface, _, _)) = message.body().deserialize::<(String, HashMap<String, OwnedValue>, Vec<String>)>()
+                else {
+                    continue;
+                };
+                if !WATCHED_INTERFACES.contains(&interface.as_str()) {
+                    continue;
+                }
+                let Some(path) = header.path().map(|p| p.to_string()) else { continue };
+                StorageEvent {
+                    action: "changed".into(),
+                    device: devices.get(&path).cloned(),
+                    object_path: path,
+                    interfaces: vec![interface],
+                }
+            }
+            _ => continue,
+        };
+
+        if let Err(e) = app_handle.emit(STORAGE_EVENT, &event) {
+            eprintln!("Failed to emit storage event: {}", e);
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tauri commands
+// -----------------------------------------------------------------------------
+
+/// Mounts under /run/media/$USER and returns the mount point.
+#[tauri::command]
+pub async fn mount_filesystem(device: String, options: Option<String>) -> Result<String, String> {
+    let conn = connect().await?;
+    let block = resolve_block(&conn, &device).await?;
+    let mut mount_options = HashMap::new();
+    if let Some(options) = options.filter(|o| !o.trim().is_empty()) {
+        mount_options.insert("options", Value::from(options));
+    }
+    call(&conn, &block, FILESYSTEM, "Mount", &(mount_options,))
+        .await
+        .map_err(|e| dbus_error(&format!("Failed to mount {}", device), e))
+}
+
+#[tauri::command]
+pub async fn unmount_filesystem(device: String, force: Option<bool>) -> Result<String, String> {
+    let conn = connect().await?;
+    let block = resolve_block(&conn, &device).await?;
+    unmount_block(&conn, &block, &device, force.unwrap_or(false)).await?;
+    Ok(format!("Unmounted {}.", device))
+}
+
+/// Returns the cleartext device, e.g. "/dev/dm-3", ready to be mounted.
+#[tauri::command]
+pub async fn unlock_luks_volume(device: String, passphrase: String) -> Result<String, String> {
+    let conn = connect().await?;
+    let block = resolve_block(&conn, &device).await?;
+    let cleartext: OwnedObjectPath = call(&conn, &block, ENCRYPTED, "Unlock", &(passphrase, no_options()))
+        .await
+        .map_err(|e| dbus_error(&format!("Failed to unlock {}", device), e))?;
+    let objects = managed_objects(&conn).await?;
+    Ok(device_of(&objects, &cleartext).unwrap_or_else(|| cleartext.to_string()))
+}
+
+#[tauri::command]
+pub async fn lock_luks_volume(device: String) -> Result<String, String> {
+    let conn = connect().await?;
+    let block = resolve_block(&conn, &device).await?;
+    call::<_, ()>(&conn, &block, ENCRYPTED, "Lock", &(no_options(),))
+        .await
+        .map_err(|e| dbus_error(&format!("Failed to lock {}", device), e))?;
+    Ok(format!("Locked {}.", device))
+}
+
+/// `device` may be the disk or any partition on it.
+#[tauri::command]
+pub async fn eject_drive(device: String) -> Result<String, String> {
+    let conn = connect().await?;
+    let drive = drive_of(&conn, &device).await?;
+    release_drive(&conn, &drive).await?;
+    call::<_, ()>(&conn, &drive, DRIVE, "Eject", &(no_options(),))
+        .await
+        .map_err(|e| dbus_error(&format!("Failed to eject {}", device), e))?;
+    Ok(format!("Ejected {}.", device))
+}
+
+/// Unmounts and locks everything on the drive, then cuts its power so it can be unplugged.
+#[tauri::command]
+pub async fn power_off_drive(device: String) -> Result<String, String> {
+    let conn = connect().await?;
+    let drive = drive_of(&conn, &device).await?;
+    let objects = managed_objects(&conn).await?;
+    if property(&objects, &drive, DRIVE, "CanPowerOff").and_then(|v| bool::try_from(v).ok()) == Some(false) {
+        return Err(format!("{} cannot be powered off.", device));
+    }
+    release_drive(&conn, &drive).await?;
+    call::<_, ()>(&conn, &drive, DRIVE, "PowerOff", &(no_options(),))
+        .await
+        .map_err(|e| dbus_error(&format!("Failed to power off {}", device), e))?;
+    Ok(format!("{} can now be unplugged.", device))
+}
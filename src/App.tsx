@@ -193,8 +193,8 @@ const App: React.FC = () => {
       if (unlisten) {
         unlisten();
       }
-      // Optional: You might want a command to stop the monitor on cleanup
-      // invoke('stop_system_monitor').catch(console.error);
+      // Release this view's subscription; sampling stops with the last one
+      invoke("stop_system_monitor").catch(console.error);
     };
   }, [dispatch]);
 
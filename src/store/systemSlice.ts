@@ -18,8 +18,9 @@ const systemSlice = createSlice({
   name: 'system',
   initialState,
   reducers: {
-    updateSystemInfo: (state, action: PayloadAction<SystemInfo>) => {
-      state.info = action.payload;
+    // The monitor sends every section once, then only the sections that changed
+    updateSystemInfo: (state, action: PayloadAction<Partial<SystemInfo>>) => {
+      state.info = { ...state.info, ...action.payload } as SystemInfo;
       state.status = 'streaming';
       state.error = null;
     },
@@ -196,6 +196,25 @@ export interface NetworkInfo {
     mac_address: string;
     total_received_bytes: number;
     total_transmitted_bytes: number;
+    received_bytes_per_s: number;
+    transmitted_bytes_per_s: number;
+    received_packets_per_s: number;
+    transmitted_packets_per_s: number;
+    addresses: InterfaceAddress[];
+    mtu: number;
+    operstate: string;
+    speed_mbps: number | null;
+    duplex: string | null;
+    wireless: boolean;
+    is_default_route: boolean;
+    default_gateway_v4: string | null;
+    default_gateway_v6: string | null;
+}
+
+export interface InterfaceAddress {
+    address: string;
+    prefix: number;
+    family: 'ipv4' | 'ipv6';
 }
 
 export interface UserInfo {
@@ -215,4 +234,52 @@ export interface SystemInfo {
     components: SensorInfo[]; // 'components' is used for sensors in sysinfo crate
     networks: NetworkInfo[];
     users: UserInfo[];
+    power: PowerInfo;
+    sensors: HwmonSensor[];
+}
+
+export interface HwmonSensor {
+    id: string;
+    chip: string;
+    kind: 'temperature' | 'fan' | 'voltage' | 'current' | 'power';
+    label: string;
+    value: number;
+    unit: string;
+    min: number | null;
+    max: number | null;
+    critical: number | null;
+}
+
+export interface PowerInfo {
+    on_ac: boolean | null;
+    adapters: PowerAdapter[];
+    batteries: BatteryInfo[];
+}
+
+export interface PowerAdapter {
+    name: string;
+    kind: string;
+    online: boolean;
+}
+
+export interface BatteryInfo {
+    name: string;
+    system: boolean;
+    manufacturer: string | null;
+    model: string | null;
+    serial: string | null;
+    technology: string | null;
+    status: string;
+    capacity_percent: number | null;
+    energy_now_wh: number | null;
+    energy_full_wh: number | null;
+    energy_full_design_wh: number | null;
+    health_percent: number | null;
+    cycle_count: number | null;
+    power_w: number | null;
+    voltage_v: number | null;
+    time_to_empty_s: number | null;
+    time_to_full_s: number | null;
+    charge_start_threshold: number | null;
+    charge_end_threshold: number | null;
 }
\ No newline at end of file
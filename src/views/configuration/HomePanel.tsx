@@ -22,6 +22,12 @@ interface InstalledKernel {
   name: string;
   version: string;
   flavor: string;
+  pkgbase: string | null;
+  kernel_release: string;
+  headers_package: string | null;
+  headers_installed: boolean;
+  running: boolean;
+  orphaned: boolean;
 }
 
 const HomePanel: React.FC<{ setActivePanel: (panel: ConfigPanel) => void }> = ({
@@ -90,7 +96,6 @@ const HomePanel: React.FC<{ setActivePanel: (panel: ConfigPanel) => void }> = ({
         // Kernel Info
         const kernelResult: string = await invoke("get_system_kernels");
         const kernelData = JSON.parse(kernelResult);
-        const runningVersion = kernelData.running_kernel;
         const kernelMap = new Map<string, Kernel>();
         (kernelData.installed_kernels as InstalledKernel[]).forEach((k) => {
           let releaseType: Kernel["releaseType"] = "stable";
@@ -100,7 +105,7 @@ const HomePanel: React.FC<{ setActivePanel: (panel: ConfigPanel) => void }> = ({
             version: k.version,
             pkg: k.name,
             releaseType,
-            running: k.version === runningVersion,
+            running: k.running,
           });
         });
         const combinedKernels = Array.from(kernelMap.values());
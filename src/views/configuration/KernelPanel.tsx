@@ -15,6 +15,12 @@ interface InstalledKernel {
   name: string;
   version: string;
   flavor: string;
+  pkgbase: string | null;
+  kernel_release: string;
+  headers_package: string | null;
+  headers_installed: boolean;
+  running: boolean;
+  orphaned: boolean;
 }
 
 interface InstallableKernel {
@@ -22,6 +28,7 @@ interface InstallableKernel {
   version: string;
   description: string;
   flavor: string;
+  repository: string;
 }
 
 interface KernelData {
@@ -99,7 +106,6 @@ const KernelPanel: React.FC = () => {
         const result: string = await invoke("get_system_kernels");
         const kernelData: KernelData = JSON.parse(result);
 
-        const runningVersion = kernelData.running_kernel;
         setInstalledKernels(kernelData.installed_kernels);
 
         const kernelMap = new Map<string, Kernel>();
@@ -115,7 +121,7 @@ const KernelPanel: React.FC = () => {
             version: k.version,
             pkg: k.name,
             releaseType,
-            running: k.version === runningVersion,
+            running: k.running,
           });
         });
 
@@ -123,9 +129,9 @@ const KernelPanel: React.FC = () => {
         kernelData.installable_kernels
           .filter(
             (k) =>
-              k.package_name.startsWith("linux-") &&
-              k.package_name.endsWith("-meta") &&
-              !k.package_name.includes("headers")
+              !kernelData.installed_kernels.some(
+                (ik) => ik.name === k.package_name
+              )
           )
           .forEach((k) => {
             const key = k.package_name;
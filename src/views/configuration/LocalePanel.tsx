@@ -21,6 +21,7 @@ interface LocaleStatus {
   available_locales: string[];
   generated_locales: string[];
   reboot_required: boolean;
+  reboot_reasons?: { kind: string; message: string }[];
 }
 
 const localeIdToName = (id: string): string => {
@@ -45,7 +46,7 @@ const localeIdToName = (id: string): string => {
   }
 };
 
-const RebootRequired: React.FC = () => (
+const RebootRequired: React.FC<{ reasons: string[] }> = ({ reasons }) => (
   <motion.div
     initial={{ opacity: 0, y: -10 }}
     animate={{ opacity: 1, y: 0 }}
@@ -53,7 +54,16 @@ const RebootRequired: React.FC = () => (
     className="flex items-center gap-3 p-3 mb-4 text-sm font-semibold text-yellow-800 dark:text-yellow-200 bg-yellow-400/20 dark:bg-yellow-500/10 rounded-lg border border-yellow-400/30 dark:border-yellow-500/20"
   >
     <AlertCircle className="w-5 h-5 flex-shrink-0" />
-    <p>A reboot is required for all changes to take full effect.</p>
+    <div>
+      <p>A reboot is required for all changes to take full effect.</p>
+      {reasons.length > 0 && (
+        <ul className="mt-1 font-normal list-disc list-inside">
+          {reasons.map((reason) => (
+            <li key={reason}>{reason}</li>
+          ))}
+        </ul>
+      )}
+    </div>
   </motion.div>
 );
 
@@ -378,7 +388,11 @@ const LocalePanel: React.FC = () => {
   return (
     <Panel title={t("locale_settings")}>
       <AnimatePresence>
-        {localeStatus?.reboot_required && <RebootRequired />}
+        {localeStatus?.reboot_required && (
+          <RebootRequired
+            reasons={(localeStatus.reboot_reasons ?? []).map((r) => r.message)}
+          />
+        )}
       </AnimatePresence>
 
       <div className="flex justify-end items-center gap-2 mb-4 -mt-4">